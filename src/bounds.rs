@@ -0,0 +1,172 @@
+use crate::mercator::tile_to_meters_frac;
+use crate::{tile2lonlat, tile2lonlat_frac, tile_to_meters, Tile, TileRange};
+
+/// Axis-aligned bounding box.
+///
+/// Depending on where it comes from, the bounds are either lon/lat coordinates in degrees
+/// or Web Mercator (EPSG:3857) coordinates in meters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bounds {
+	/// Western (minimum X) edge.
+	pub west: f64,
+	/// Southern (minimum Y) edge.
+	pub south: f64,
+	/// Eastern (maximum X) edge.
+	pub east: f64,
+	/// Northern (maximum Y) edge.
+	pub north: f64,
+}
+
+impl Bounds {
+	/// Create a bounding box from its west, south, east and north edges.
+	pub fn new(west: f64, south: f64, east: f64, north: f64) -> Self {
+		Self { west, south, east, north }
+	}
+
+	/// Bounds as a `[west, south, east, north]` array, the order used by WMS and TileJSON.
+	pub fn to_array(&self) -> [f64; 4] {
+		[self.west, self.south, self.east, self.north]
+	}
+}
+
+/// Serializes the bounds as a `[west, south, east, north]` array.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Bounds {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		self.to_array().serialize(serializer)
+	}
+}
+
+/// Deserializes the bounds from a `[west, south, east, north]` array.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Bounds {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let [west, south, east, north] = <[f64; 4]>::deserialize(deserializer)?;
+		Ok(Self::new(west, south, east, north))
+	}
+}
+
+/// Bounding box of a Web Mercator tile in lon/lat coordinates, in degrees.
+///
+/// # Arguments
+///
+/// * `x`    - X tile coordinate
+/// * `y`    - Y tile coordinate
+/// * `zoom` - zoom level
+pub fn tile_bounds(x: u32, y: u32, zoom: u8) -> Bounds {
+	let (west, north) = tile2lonlat(x, y, zoom);
+	let (east, south) = tile2lonlat_frac(x as f64 + 1f64, y as f64 + 1f64, zoom);
+	Bounds { west, south, east, north }
+}
+
+/// Bounding box of a Web Mercator tile in EPSG:3857 coordinates, in meters.
+///
+/// # Arguments
+///
+/// * `x`    - X tile coordinate
+/// * `y`    - Y tile coordinate
+/// * `zoom` - zoom level
+pub fn tile_bounds_meters(x: u32, y: u32, zoom: u8) -> Bounds {
+	let (west, north) = tile_to_meters(x, y, zoom);
+	let (east, south) = tile_to_meters_frac(x as f64 + 1f64, y as f64 + 1f64, zoom);
+	Bounds { west, south, east, north }
+}
+
+/// Center of a Web Mercator tile in lon/lat coordinates, in degrees.
+///
+/// The center is taken in projected space, so its latitude is not the mean
+/// of the northern and southern edges of the tile.
+///
+/// # Arguments
+///
+/// * `x`    - X tile coordinate
+/// * `y`    - Y tile coordinate
+/// * `zoom` - zoom level
+pub fn tile_center(x: u32, y: u32, zoom: u8) -> (f64, f64) {
+	tile2lonlat_frac(x as f64 + 0.5, y as f64 + 0.5, zoom)
+}
+
+impl Tile {
+	/// Bounding box of this tile in degrees, see [`tile_bounds`].
+	pub fn bounds(&self) -> Bounds {
+		tile_bounds(self.x(), self.y(), self.z())
+	}
+
+	/// Bounding box of this tile in meters, see [`tile_bounds_meters`].
+	pub fn bounds_meters(&self) -> Bounds {
+		tile_bounds_meters(self.x(), self.y(), self.z())
+	}
+
+	/// Center of this tile in degrees, see [`tile_center`].
+	pub fn center(&self) -> (f64, f64) {
+		tile_center(self.x(), self.y(), self.z())
+	}
+}
+
+impl TileRange {
+	/// Bounding box of the tiles of this range in degrees.
+	pub fn bounds(&self) -> Bounds {
+		let (west, north) = tile2lonlat(self.min_x(), self.min_y(), self.zoom());
+		let (east, south) = tile2lonlat_frac(self.max_x() as f64 + 1f64, self.max_y() as f64 + 1f64, self.zoom());
+		Bounds { west, south, east, north }
+	}
+
+	/// Center of the tiles of this range in degrees.
+	///
+	/// Like [`tile_center`], the latitude is taken at the middle row of the range,
+	/// which is not the average of its northern and southern edges.
+	pub fn center(&self) -> (f64, f64) {
+		let x = (self.min_x() as f64 + self.max_x() as f64 + 1f64) / 2f64;
+		let y = (self.min_y() as f64 + self.max_y() as f64 + 1f64) / 2f64;
+		tile2lonlat_frac(x, y, self.zoom())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{MAX_EXTENT, MAX_LATITUDE};
+
+	#[test]
+	fn test_bounds() {
+		assert_eq!(tile_bounds(0, 0, 0), Bounds::new(-180.0, -MAX_LATITUDE, 180.0, MAX_LATITUDE));
+		assert_eq!(tile_bounds(1, 0, 1), Bounds::new(0.0, 0.0, 180.0, MAX_LATITUDE));
+
+		let bounds = tile_bounds(4376, 2932, 13);
+		assert_eq!((bounds.west, bounds.north), tile2lonlat(4376, 2932, 13));
+		assert_eq!((bounds.east, bounds.south), tile2lonlat(4377, 2933, 13));
+	}
+
+	#[test]
+	fn test_bounds_meters() {
+		assert_eq!(tile_bounds_meters(0, 0, 0), Bounds::new(-MAX_EXTENT, -MAX_EXTENT, MAX_EXTENT, MAX_EXTENT));
+		assert_eq!(tile_bounds_meters(0, 1, 1).to_array(), [-MAX_EXTENT, -MAX_EXTENT, 0.0, 0.0]);
+		assert_eq!(tile_bounds_meters(4376, 2932, 13).west, tile_to_meters(4376, 2932, 13).0);
+		assert_eq!(tile_bounds_meters(4376, 2932, 13).east, tile_to_meters(4377, 2933, 13).0);
+
+		// Invalid coordinates give an invalid result instead of overflowing.
+		assert!(tile_bounds(u32::MAX, u32::MAX, 1).east > 180.0);
+		assert!(tile_bounds_meters(u32::MAX, u32::MAX, 1).east > MAX_EXTENT);
+	}
+
+	#[test]
+	fn test_center() {
+		assert_eq!(tile_center(0, 0, 0), (0.0, 0.0));
+		let (lon, lat) = Tile::new(0, 0, 1).unwrap().center();
+		assert_eq!(lon, -90.0);
+		assert!((lat - 66.51326044311186).abs() < 1e-9);
+	}
+
+	#[test]
+	fn test_range() {
+		let range = TileRange::new(0, 0, 1, 1, 1).unwrap();
+		assert_eq!(range.bounds(), tile_bounds(0, 0, 0));
+		assert_eq!(range.center(), (0.0, 0.0));
+
+		let range = TileRange::new(4376, 2932, 4377, 2935, 13).unwrap();
+		let bounds = range.bounds();
+		assert_eq!((bounds.west, bounds.north), tile2lonlat(4376, 2932, 13));
+		assert_eq!((bounds.east, bounds.south), tile2lonlat(4378, 2936, 13));
+		assert_eq!(range.center(), tile2lonlat(4377, 2934, 13));
+	}
+}
@@ -0,0 +1,349 @@
+use std::collections::HashSet;
+
+use crate::{lonlat2tile_frac, tile_center, Tile, TileError, MAX_EXTENT, MAX_LATITUDE, MAX_ZOOM};
+
+/// Width of the corridor added around a line by [`line_tiles_buffered`].
+///
+/// Negative or NaN buffers are treated as no buffer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Buffer {
+	/// Number of tiles added on each side of the tiles crossed by the line.
+	Tiles(u32),
+	/// Ground distance added on each side of the line, in meters, rounded up to whole tiles
+	/// at the latitude of each tile.
+	Meters(f64),
+}
+
+/// Find the tiles crossed by a lon/lat line string at a given zoom level.
+///
+/// Unlike converting each vertex with [`crate::lonlat2tile`], every tile crossed by
+/// the segments between the vertices is included, so the tiles form a connected path.
+/// Latitudes are clamped to [`MAX_LATITUDE`], and lines crossing the antimeridian
+/// are not supported.
+///
+/// The tiles are returned in row-major order (west to east, then north to south).
+///
+/// # Arguments
+///
+/// * `line` - lon/lat coordinates, in degrees
+/// * `zoom` - zoom level
+pub fn line_tiles(line: &[(f64, f64)], zoom: u8) -> Result<Vec<Tile>, TileError> {
+	Ok(sorted(cover_line(line, zoom)?, zoom))
+}
+
+/// Find the tiles within a corridor around a lon/lat line string at a given zoom level.
+///
+/// The tiles crossed by the line, as found by [`line_tiles`], are extended by the buffer
+/// in every direction, as with [`Tile::k_ring`]. The corridor wraps around the antimeridian
+/// but stops at the poles.
+///
+/// # Arguments
+///
+/// * `line`   - lon/lat coordinates, in degrees
+/// * `zoom`   - zoom level
+/// * `buffer` - width of the corridor on each side of the line
+pub fn line_tiles_buffered(line: &[(f64, f64)], zoom: u8, buffer: Buffer) -> Result<Vec<Tile>, TileError> {
+	let path = cover_line(line, zoom)?;
+	let n = 1u64 << zoom;
+	let mut tiles = HashSet::new();
+	for &(x, y) in &path {
+		let radius = match buffer {
+			Buffer::Tiles(tiles) => tiles,
+			Buffer::Meters(meters) => {
+				let (_, lat) = tile_center(x, y, zoom);
+				let tile_width = 2f64 * MAX_EXTENT * lat.to_radians().cos() / n as f64;
+				(meters / tile_width).ceil() as u32
+			}
+		}
+		.min(n as u32);
+
+		let ring = Tile::new(x, y, zoom).map(|tile| tile.k_ring(radius)).unwrap_or_default();
+		tiles.extend(ring.into_iter().map(|tile| (tile.x(), tile.y())));
+	}
+	Ok(sorted(tiles, zoom))
+}
+
+fn cover_line(line: &[(f64, f64)], zoom: u8) -> Result<HashSet<(u32, u32)>, TileError> {
+	if zoom > MAX_ZOOM {
+		return Err(TileError::InvalidZoom(zoom));
+	}
+	let mut tiles = HashSet::new();
+	cover_path(&project(line, zoom)?, last_cell(zoom), &mut tiles);
+	Ok(tiles)
+}
+
+/// Find the tiles intersecting a lon/lat polygon at a given zoom level.
+///
+/// The first ring is the exterior ring, the following ones are interior rings (holes).
+/// Rings may or may not repeat their first point at the end. Tiles lying entirely inside
+/// of a hole are excluded, tiles crossed by the edge of a hole are included.
+/// Latitudes are clamped to [`MAX_LATITUDE`], and polygons crossing the antimeridian
+/// are not supported.
+///
+/// The tiles are returned in row-major order (west to east, then north to south).
+///
+/// # Arguments
+///
+/// * `polygon` - rings of lon/lat coordinates, in degrees
+/// * `zoom`    - zoom level
+pub fn polygon_tiles(polygon: &[Vec<(f64, f64)>], zoom: u8) -> Result<Vec<Tile>, TileError> {
+	if zoom > MAX_ZOOM {
+		return Err(TileError::InvalidZoom(zoom));
+	}
+	let mut tiles = HashSet::new();
+	add_polygon(polygon, zoom, &mut tiles)?;
+	Ok(sorted(tiles, zoom))
+}
+
+/// Find the tiles intersecting a lon/lat multipolygon at a given zoom level.
+///
+/// Each polygon is covered as in [`polygon_tiles`], and the result is their union.
+///
+/// # Arguments
+///
+/// * `multipolygon` - polygons made of rings of lon/lat coordinates, in degrees
+/// * `zoom`         - zoom level
+pub fn multipolygon_tiles(multipolygon: &[Vec<Vec<(f64, f64)>>], zoom: u8) -> Result<Vec<Tile>, TileError> {
+	if zoom > MAX_ZOOM {
+		return Err(TileError::InvalidZoom(zoom));
+	}
+
+	let mut tiles = HashSet::new();
+	for polygon in multipolygon {
+		add_polygon(polygon, zoom, &mut tiles)?;
+	}
+	Ok(sorted(tiles, zoom))
+}
+
+fn add_polygon(polygon: &[Vec<(f64, f64)>], zoom: u8, tiles: &mut HashSet<(u32, u32)>) -> Result<(), TileError> {
+	let rings = polygon.iter().map(|ring| project(ring, zoom)).collect::<Result<Vec<_>, _>>()?;
+	cover_polygon(&rings, last_cell(zoom), tiles);
+	Ok(())
+}
+
+/// Project lon/lat coordinates to fractional tile coordinates.
+pub(crate) fn project(points: &[(f64, f64)], zoom: u8) -> Result<Vec<(f64, f64)>, TileError> {
+	points
+		.iter()
+		.map(|&(lon, lat)| {
+			if !lon.is_finite() || !lat.is_finite() {
+				return Err(TileError::NonFinite { lon, lat });
+			}
+			if !(-180f64..=180f64).contains(&lon) {
+				return Err(TileError::LongitudeOutOfRange(lon));
+			}
+			Ok(lonlat2tile_frac(lon, lat.clamp(-MAX_LATITUDE, MAX_LATITUDE), zoom))
+		})
+		.collect()
+}
+
+/// Convert a set of `(x, y)` coordinates to tiles in row-major order.
+pub(crate) fn sorted(tiles: HashSet<(u32, u32)>, zoom: u8) -> Vec<Tile> {
+	let mut tiles: Vec<_> = tiles.into_iter().collect();
+	tiles.sort_unstable_by_key(|&(x, y)| (y, x));
+	tiles.into_iter().filter_map(|(x, y)| Tile::new(x, y, zoom).ok()).collect()
+}
+
+/// Column and row of the last tile at a given zoom level.
+fn last_cell(zoom: u8) -> (i64, i64) {
+	let max = (1i64 << zoom) - 1;
+	(max, max)
+}
+
+// Tolerance for edges computed from tile coordinates, which may be off by a few ulps.
+const EPSILON: f64 = 1e-9;
+
+/// Cell of a fractional tile coordinate, clamped to the map.
+pub(crate) fn cell(v: f64, max: i64) -> i64 {
+	(v.floor() as i64).clamp(0, max)
+}
+
+/// First and last cells covered by an interval of fractional tile coordinates, clamped to the map.
+///
+/// An end lying exactly on a tile boundary does not include the tile beyond that boundary.
+pub(crate) fn cell_span(start: f64, end: f64, max: i64) -> (u32, u32) {
+	let first = cell(start + EPSILON, max);
+	let last = cell((end - EPSILON).ceil() - 1f64, max).max(first);
+	(first as u32, last as u32)
+}
+
+/// Add the tiles crossed by a line string in tile space, up to the column and row `max`.
+pub(crate) fn cover_path(points: &[(f64, f64)], max: (i64, i64), tiles: &mut HashSet<(u32, u32)>) {
+	if let [point] = points[..] {
+		tiles.insert((cell(point.0 + EPSILON, max.0) as u32, cell(point.1 + EPSILON, max.1) as u32));
+	}
+	for segment in points.windows(2) {
+		cover_segment(segment[0], segment[1], max, true, tiles);
+	}
+}
+
+/// Add the tiles whose interior is crossed by a segment in tile space.
+///
+/// With `along_edges`, the tiles form a path without gaps: a segment running along a tile edge
+/// adds the tiles south or east of it, and a segment crossing a tile corner adds one of the tiles
+/// touching the corner. Otherwise these tiles are left out, as for the edges of a polygon, whose
+/// inside is filled separately.
+fn cover_segment(a: (f64, f64), b: (f64, f64), max: (i64, i64), along_edges: bool, tiles: &mut HashSet<(u32, u32)>) {
+	let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+	let length = dx.abs().max(dy.abs());
+
+	// Parameters along the segment at which it crosses a vertical or horizontal tile edge.
+	let crossings = |start: f64, d: f64| {
+		let (lo, hi) = if d == 0f64 { (1f64, 0f64) } else { (start.min(start + d).ceil(), start.max(start + d).floor()) };
+		(lo as i64..=hi as i64).map(move |k| (k as f64 - start) / d)
+	};
+	let mut ts: Vec<f64> = [0f64, 1f64].into_iter().chain(crossings(a.0, dx)).chain(crossings(a.1, dy)).collect();
+	ts.sort_unstable_by(f64::total_cmp);
+
+	// Each piece between two crossings lies inside a single tile, or along a tile edge.
+	let on_edge = |v: f64| (v - v.round()).abs() < EPSILON;
+	let mut previous: Option<(i64, i64)> = None;
+	for piece in ts.windows(2) {
+		if (piece[1] - piece[0]) * length < EPSILON {
+			continue;
+		}
+		let t = (piece[0] + piece[1]) / 2f64;
+		let (x, y) = (a.0 + t * dx, a.1 + t * dy);
+		if !along_edges && (on_edge(x) || on_edge(y)) {
+			continue;
+		}
+		let (x, y) = (cell(x + EPSILON, max.0), cell(y + EPSILON, max.1));
+		if let Some((_, py)) = previous.filter(|&(px, py)| along_edges && px != x && py != y) {
+			tiles.insert((x as u32, py as u32));
+		}
+		tiles.insert((x as u32, y as u32));
+		previous = Some((x, y));
+	}
+}
+
+/// Add the tiles intersecting a polygon given in tile space, up to the column and row `max`.
+pub(crate) fn cover_polygon(rings: &[Vec<(f64, f64)>], max: (i64, i64), tiles: &mut HashSet<(u32, u32)>) {
+	let edges = || rings.iter().filter(|r| !r.is_empty()).flat_map(|r| r.iter().zip(r.iter().cycle().skip(1)));
+
+	// Tiles crossed by the rings.
+	for (&a, &b) in edges() {
+		cover_segment(a, b, max, false, tiles);
+	}
+
+	// Tiles inside of the rings, filled with the even-odd rule along the center line of each row.
+	let (min_y, max_y) = edges().fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), (a, _)| (lo.min(a.1), hi.max(a.1)));
+	if min_y > max_y {
+		return;
+	}
+	let mut crossings = Vec::new();
+	for row in cell(min_y, max.1)..=cell(max_y, max.1) {
+		let center = row as f64 + 0.5;
+		crossings.clear();
+		for (&(x0, y0), &(x1, y1)) in edges() {
+			if (y0 <= center) != (y1 <= center) {
+				crossings.push(x0 + (center - y0) / (y1 - y0) * (x1 - x0));
+			}
+		}
+		crossings.sort_unstable_by(f64::total_cmp);
+		for pair in crossings.chunks_exact(2).filter(|pair| pair[1] - pair[0] >= EPSILON) {
+			let (first, last) = cell_span(pair[0], pair[1], max.0);
+			for x in first..=last {
+				tiles.insert((x, row as u32));
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_line() {
+		let line = [(0.5, 0.5), (2.5, 1.5)].map(|(x, y)| crate::tile2lonlat_frac(x, y, 3));
+		assert_eq!(line_tiles(&line, 3).unwrap(), [(0, 0), (1, 0), (1, 1), (2, 1)].map(|(x, y)| Tile::new(x, y, 3).unwrap()));
+
+		let route = [(12.3046875, 45.460130637921), (12.5, 45.5), (12.1, 45.1)];
+		let tiles = line_tiles(&route, 13).unwrap();
+		for tile in &tiles {
+			let connected = tiles.iter().any(|t| t != tile && t.x().abs_diff(tile.x()) + t.y().abs_diff(tile.y()) == 1);
+			assert!(connected);
+		}
+
+		assert_eq!(line_tiles(&[(0.0, 0.0)], 1).unwrap(), [Tile::new(1, 1, 1).unwrap()]);
+		assert_eq!(line_tiles(&[], 1).unwrap(), []);
+		assert!(line_tiles(&[(0.0, f64::NAN)], 1).is_err());
+	}
+
+	#[test]
+	fn test_line_buffered() {
+		let point = [crate::tile2lonlat_frac(0.5, 3.5, 3)];
+		let tiles = line_tiles_buffered(&point, 3, Buffer::Tiles(1)).unwrap();
+		let expected = [(0, 2), (1, 2), (7, 2), (0, 3), (1, 3), (7, 3), (0, 4), (1, 4), (7, 4)];
+		assert_eq!(tiles, expected.map(|(x, y)| Tile::new(x, y, 3).unwrap()));
+
+		let everything = line_tiles_buffered(&point, 1, Buffer::Tiles(5)).unwrap();
+		assert_eq!(everything.len(), 4);
+
+		// Tiles at zoom 10 are about 39km wide at the equator.
+		let equator = [(0.1, 0.1)];
+		assert_eq!(line_tiles_buffered(&equator, 10, Buffer::Meters(1000.0)).unwrap().len(), 9);
+		assert_eq!(line_tiles_buffered(&equator, 10, Buffer::Meters(50000.0)).unwrap().len(), 25);
+		assert_eq!(line_tiles_buffered(&equator, 10, Buffer::Meters(f64::NAN)).unwrap().len(), 1);
+	}
+
+	#[test]
+	fn test_polygon() {
+		let square = vec![(-10.0, -10.0), (10.0, -10.0), (10.0, 10.0), (-10.0, 10.0), (-10.0, -10.0)];
+		let tiles = polygon_tiles(std::slice::from_ref(&square), 1).unwrap();
+		assert_eq!(tiles, [(0, 0), (1, 0), (0, 1), (1, 1)].map(|(x, y)| Tile::new(x, y, 1).unwrap()));
+		assert_eq!(polygon_tiles(&[square], 0).unwrap(), [Tile::new(0, 0, 0).unwrap()]);
+
+		// Diagonal triangle in tile space: the tiles in the opposite corner are not included.
+		let triangle = vec![(0.5, 0.5), (3.2, 0.5), (0.5, 3.2)];
+		let triangle = triangle.into_iter().map(|(x, y)| crate::tile2lonlat_frac(x, y, 2)).collect();
+		let tiles = polygon_tiles(&[triangle], 2).unwrap();
+		let expected = [(0, 0), (1, 0), (2, 0), (3, 0), (0, 1), (1, 1), (2, 1), (0, 2), (1, 2), (0, 3)];
+		assert_eq!(tiles, expected.map(|(x, y)| Tile::new(x, y, 2).unwrap()));
+	}
+
+	#[test]
+	fn test_edges() {
+		// Tiles only touched by the geometry along their edges are not included.
+		let b = crate::tile_bounds(3, 3, 3);
+		let square = vec![(b.west, b.south), (b.east, b.south), (b.east, b.north), (b.west, b.north)];
+		assert_eq!(polygon_tiles(&[square], 3).unwrap(), [Tile::new(3, 3, 3).unwrap()]);
+		let range = &crate::TileRange::from_bounds(&b, 3).unwrap()[0];
+		assert_eq!((range.min_x(), range.min_y(), range.max_x(), range.max_y()), (3, 3, 3, 3));
+
+		let l_shape = vec![(0.0, 0.0), (2.0, 0.0), (2.0, 1.0), (1.0, 1.0), (1.0, 2.0), (0.0, 2.0)];
+		let l_shape = l_shape.into_iter().map(|(x, y)| crate::tile2lonlat_frac(x, y, 2)).collect();
+		assert_eq!(polygon_tiles(&[l_shape], 2).unwrap(), [(0, 0), (1, 0), (0, 1)].map(|(x, y)| Tile::new(x, y, 2).unwrap()));
+
+		let line = [(3.0, 4.0), (5.0, 4.0)].map(|(x, y)| crate::tile2lonlat_frac(x, y, 3));
+		assert_eq!(line_tiles(&line, 3).unwrap(), [(3, 4), (4, 4)].map(|(x, y)| Tile::new(x, y, 3).unwrap()));
+		let line = [(4.0, 5.0), (4.0, 3.0)].map(|(x, y)| crate::tile2lonlat_frac(x, y, 3));
+		assert_eq!(line_tiles(&line, 3).unwrap(), [(4, 3), (4, 4)].map(|(x, y)| Tile::new(x, y, 3).unwrap()));
+		let diagonal = [(0.5, 0.5), (2.0, 2.0)].map(|(x, y)| crate::tile2lonlat_frac(x, y, 3));
+		assert_eq!(line_tiles(&diagonal, 3).unwrap(), [(0, 0), (1, 0), (1, 1)].map(|(x, y)| Tile::new(x, y, 3).unwrap()));
+	}
+
+	#[test]
+	fn test_polygon_hole() {
+		let exterior = vec![(-180.0, -85.0), (180.0, -85.0), (180.0, 85.0), (-180.0, 85.0)];
+		let (west, north) = crate::tile2lonlat(2, 2, 3);
+		let (east, south) = crate::tile2lonlat(6, 6, 3);
+		let (w, s, e, n) = (west + 1e-6, south + 1e-6, east - 1e-6, north - 1e-6);
+		let hole = vec![(w, s), (w, n), (e, n), (e, s)];
+
+		let tiles = polygon_tiles(&[exterior, hole], 3).unwrap();
+		assert_eq!(tiles.len(), 64 - 4);
+		for (x, y) in [(3, 3), (4, 3), (3, 4), (4, 4)] {
+			assert!(!tiles.contains(&Tile::new(x, y, 3).unwrap()));
+		}
+	}
+
+	#[test]
+	fn test_multipolygon() {
+		let a = vec![vec![(-170.0, 80.0), (-160.0, 80.0), (-160.0, 70.0)]];
+		let b = vec![vec![(170.0, -80.0), (160.0, -80.0), (160.0, -70.0)]];
+		assert_eq!(multipolygon_tiles(&[a, b], 1).unwrap(), [(0, 0), (1, 1)].map(|(x, y)| Tile::new(x, y, 1).unwrap()));
+		assert!(multipolygon_tiles(&[vec![vec![(f64::NAN, 0.0)]]], 1).is_err());
+		assert_eq!(multipolygon_tiles(&[], 1).unwrap(), []);
+	}
+}
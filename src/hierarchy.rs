@@ -0,0 +1,172 @@
+use crate::{Tile, TileRange, TileRangeIter, MAX_ZOOM};
+
+impl Tile {
+	/// Ancestor of this tile at the given zoom level.
+	///
+	/// Returns the tile itself at its own zoom level, or `None` if `z` is greater than the
+	/// zoom level of this tile.
+	///
+	/// # Arguments
+	///
+	/// * `z` - zoom level of the ancestor
+	pub fn parent_at(&self, z: u8) -> Option<Tile> {
+		let shift = self.z().checked_sub(z)?;
+		Tile::new(self.x() >> shift, self.y() >> shift, z).ok()
+	}
+
+	/// Iterate over the ancestors of this tile, from its parent up to zoom level 0.
+	pub fn ancestors(&self) -> Ancestors {
+		Ancestors { tile: *self }
+	}
+
+	/// Range of the descendants of this tile at the given zoom level.
+	///
+	/// Returns a range made of the tile itself at its own zoom level, or `None` if `z`
+	/// is smaller than the zoom level of this tile or greater than [`MAX_ZOOM`].
+	///
+	/// # Arguments
+	///
+	/// * `z` - zoom level of the descendants
+	pub fn descendant_range(&self, z: u8) -> Option<TileRange> {
+		let shift = z.checked_sub(self.z()).filter(|_| z <= MAX_ZOOM)?;
+		let (min_x, min_y) = (self.x() << shift, self.y() << shift);
+		let size = (1u32 << shift) - 1;
+		TileRange::new(min_x, min_y, min_x + size, min_y + size, z).ok()
+	}
+
+	/// Iterate over the descendants of this tile at the given zoom level, in row-major order.
+	///
+	/// The iterator is empty if `z` is smaller than the zoom level of this tile
+	/// or greater than [`MAX_ZOOM`].
+	///
+	/// # Arguments
+	///
+	/// * `z` - zoom level of the descendants
+	pub fn descendants(&self, z: u8) -> Descendants {
+		Descendants { range: self.descendant_range(z).map(TileRange::into_iter) }
+	}
+
+	/// Iterate over the descendants of this tile at every zoom level down to the given one.
+	///
+	/// The children of this tile come first, then its grandchildren, and so on until zoom level `z`.
+	/// The tile itself is not included.
+	///
+	/// # Arguments
+	///
+	/// * `z` - deepest zoom level of the descendants
+	pub fn descendants_through(&self, z: u8) -> Subtree {
+		Subtree { tile: *self, max_z: z.min(MAX_ZOOM), level: Descendants { range: None }, z: self.z() }
+	}
+}
+
+/// Iterator over the ancestors of a [`Tile`], see [`Tile::ancestors`].
+#[derive(Debug, Clone)]
+pub struct Ancestors {
+	tile: Tile,
+}
+
+impl Iterator for Ancestors {
+	type Item = Tile;
+
+	fn next(&mut self) -> Option<Tile> {
+		self.tile = self.tile.zoom_out()?;
+		Some(self.tile)
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		let n = self.tile.z() as usize;
+		(n, Some(n))
+	}
+}
+
+impl ExactSizeIterator for Ancestors {}
+
+/// Iterator over the descendants of a [`Tile`] at a single zoom level, see [`Tile::descendants`].
+#[derive(Debug, Clone)]
+pub struct Descendants {
+	range: Option<TileRangeIter>,
+}
+
+impl Iterator for Descendants {
+	type Item = Tile;
+
+	fn next(&mut self) -> Option<Tile> {
+		self.range.as_mut()?.next()
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		self.range.as_ref().map_or((0, Some(0)), TileRangeIter::size_hint)
+	}
+}
+
+/// Iterator over the descendants of a [`Tile`] at several zoom levels, see [`Tile::descendants_through`].
+#[derive(Debug, Clone)]
+pub struct Subtree {
+	tile: Tile,
+	max_z: u8,
+	level: Descendants,
+	z: u8,
+}
+
+impl Iterator for Subtree {
+	type Item = Tile;
+
+	fn next(&mut self) -> Option<Tile> {
+		loop {
+			if let Some(tile) = self.level.next() {
+				return Some(tile);
+			}
+			if self.z >= self.max_z {
+				return None;
+			}
+			self.z += 1;
+			self.level = self.tile.descendants(self.z);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_ancestors() {
+		let t = Tile::new(4376, 2932, 13).unwrap();
+		assert_eq!(t.parent_at(13), Some(t));
+		assert_eq!(t.parent_at(12), t.zoom_out());
+		assert_eq!(t.parent_at(0), Some(Tile::new(0, 0, 0).unwrap()));
+		assert_eq!(t.parent_at(14), None);
+
+		let ancestors: Vec<_> = t.ancestors().collect();
+		assert_eq!(ancestors.len(), 13);
+		assert_eq!(ancestors[0], Tile::new(2188, 1466, 12).unwrap());
+		assert_eq!(ancestors.last(), Some(&Tile::new(0, 0, 0).unwrap()));
+		assert_eq!(Tile::new(0, 0, 0).unwrap().ancestors().next(), None);
+	}
+
+	#[test]
+	fn test_descendants() {
+		let t = Tile::new(1, 1, 1).unwrap();
+		let (a, b, c, d) = t.zoom_in().unwrap();
+		assert_eq!(t.descendants(2).collect::<Vec<_>>(), [a, b, c, d]);
+		assert_eq!(t.descendants(1).collect::<Vec<_>>(), [t]);
+		assert_eq!(t.descendants(0).count(), 0);
+		assert_eq!(t.descendants(MAX_ZOOM + 1).count(), 0);
+		assert_eq!(t.descendants(5).size_hint(), (256, Some(256)));
+
+		let range = t.descendant_range(MAX_ZOOM).unwrap();
+		assert_eq!((range.min_x(), range.max_x()), (1 << 30, u32::MAX >> 1));
+		assert!(t.descendants(9).all(|d| d.parent_at(1) == Some(t)));
+	}
+
+	#[test]
+	fn test_subtree() {
+		let t = Tile::new(1, 1, 1).unwrap();
+		let subtree: Vec<_> = t.descendants_through(3).collect();
+		assert_eq!(subtree.len(), 4 + 16);
+		assert_eq!(&subtree[..4], t.descendants(2).collect::<Vec<_>>());
+		assert!(subtree[4..].iter().all(|d| d.z() == 3));
+		assert_eq!(t.descendants_through(1).count(), 0);
+		assert_eq!(Tile::new(0, 0, 30).unwrap().descendants_through(255).count(), 4);
+	}
+}
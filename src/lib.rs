@@ -5,11 +5,73 @@
 //!
 //! # Warning
 //!
-//! All the functions provided by this crate **do not** check the validity of the input.
+//! The free functions provided by this crate **do not** check the validity of the input.
 //! However, since they are based on equations, they will still return an (invalid) result.
+//! Use [`Tile`] to work with tile coordinates that are checked against their zoom level,
+//! and [`try_lonlat2tile`] to reject or clamp lon/lat coordinates outside of the map.
 
 use std::f64::consts::PI;
 
+mod bounds;
+mod cover;
+mod hierarchy;
+mod matrix;
+#[cfg(feature = "mbtiles")]
+pub mod mbtiles;
+mod mercator;
+mod mvt;
+mod neighbors;
+mod pixel;
+pub mod pmtiles;
+mod quadkey;
+mod range;
+mod resolution;
+mod scheme;
+mod tile;
+mod tile64;
+mod tilejson;
+mod url;
+mod wmts;
+
+pub use bounds::{tile_bounds, tile_bounds_meters, tile_center, Bounds};
+pub use cover::{line_tiles, line_tiles_buffered, multipolygon_tiles, polygon_tiles, Buffer};
+pub use hierarchy::{Ancestors, Descendants, Subtree};
+pub use matrix::{CornerOfOrigin, TileMatrix, TileMatrixError, TileMatrixLimits, TileMatrixSet};
+pub use mercator::{lonlat_to_meters, meters_to_lonlat, meters_to_tile, tile_to_meters, EARTH_RADIUS, MAX_EXTENT};
+pub use mvt::{local2lonlat, lonlat2local, DEFAULT_EXTENT};
+pub use pixel::{lonlat2pixel, pixel2lonlat, pixel2tile, tile2pixel, TILE_SIZE};
+pub use pmtiles::{pmtiles_id2tile, tile2pmtiles_id};
+pub use quadkey::{quadkey2tile, quadkey_children, quadkey_parent, tile2quadkey};
+pub use range::{TileRange, TileRangeIter};
+pub use resolution::{
+	ground_resolution, scale_denominator, zoom_for_resolution, zoom_for_scale_denominator, OGC_DPI, OGC_PIXEL_SIZE,
+};
+pub use scheme::{convert_y, flip_y, lonlat2tile_scheme, tile2lonlat_scheme, Scheme};
+pub use tile::{Tile, TileError, MAX_ZOOM};
+pub use tile64::{lonlat2tile64, tile2lonlat64, Tile64, MAX_ZOOM_64};
+pub use tilejson::{TileJson, VectorLayer, TILEJSON_MAX_ZOOM, TILEJSON_VERSION};
+pub use url::{ParseTileError, TemplateError, TileUrlMatcher, TileUrlTemplate};
+pub use wmts::{WmtsCapabilities, WmtsLayer};
+
+/// Northernmost latitude covered by Web Mercator tiles, in degrees.
+///
+/// The southern limit is `-MAX_LATITUDE`.
+pub const MAX_LATITUDE: f64 = 85.0511287798066;
+
+/// How [`try_lonlat2tile`] handles coordinates outside of the Web Mercator bounds.
+///
+/// Non-finite coordinates (NaN or infinite) are always rejected, regardless of the policy.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum LonLatPolicy {
+	/// Reject longitudes outside of `[-180, 180]` and latitudes beyond [`MAX_LATITUDE`].
+	#[default]
+	Error,
+	/// Clamp the longitude to `[-180, 180]` and the latitude to [`MAX_LATITUDE`].
+	Clamp,
+	/// Wrap the longitude around the antimeridian and clamp the latitude to [`MAX_LATITUDE`].
+	Wrap,
+}
+
 /// Convert lon/lat coordinates to a Web Mercator tile at a given zoom level.
 ///
 /// # Arguments
@@ -18,13 +80,71 @@ use std::f64::consts::PI;
 /// * `lat`  - latitude  coordinate (N-S), in degrees
 /// * `zoom` - zoom level
 pub fn lonlat2tile(lon: f64, lat: f64, zoom: u8) -> (u32, u32) {
+	let (x, y) = lonlat2tile_frac(lon, lat, zoom);
+	(x as u32, y as u32)
+}
+
+/// Convert lon/lat coordinates to fractional Web Mercator tile coordinates at a given zoom level.
+///
+/// The integer part is the tile returned by [`lonlat2tile`],
+/// the fractional part is the position inside of that tile.
+///
+/// # Arguments
+///
+/// * `lon`  - longitude coordinate (W-E), in degrees
+/// * `lat`  - latitude  coordinate (N-S), in degrees
+/// * `zoom` - zoom level
+pub fn lonlat2tile_frac(lon: f64, lat: f64, zoom: u8) -> (f64, f64) {
 	let lat_rad = lat.to_radians();
 	let z = 2f64.powf(zoom as f64);
-	let x = ((lon + 180f64) / 360f64 * z) as u32;
-	let y = ((1f64 - (lat_rad.tan() + (1f64 / lat_rad.cos())).ln() / PI) / 2f64 * z) as u32;
+	let x = (lon + 180f64) / 360f64 * z;
+	let y = (1f64 - (lat_rad.tan() + (1f64 / lat_rad.cos())).ln() / PI) / 2f64 * z;
 	(x, y)
 }
 
+/// Convert lon/lat coordinates to a Web Mercator tile at a given zoom level, checking the input.
+///
+/// Coordinates on the eastern or southern edge of the map (lon 180, lat -[`MAX_LATITUDE`])
+/// belong to the last column or row of tiles.
+///
+/// # Arguments
+///
+/// * `lon`    - longitude coordinate (W-E), in degrees
+/// * `lat`    - latitude  coordinate (N-S), in degrees
+/// * `zoom`   - zoom level
+/// * `policy` - how to handle coordinates outside of the Web Mercator bounds
+pub fn try_lonlat2tile(lon: f64, lat: f64, zoom: u8, policy: LonLatPolicy) -> Result<Tile, TileError> {
+	if zoom > MAX_ZOOM {
+		return Err(TileError::InvalidZoom(zoom));
+	}
+	let (lon, lat) = apply_policy(lon, lat, policy)?;
+
+	let max = (1u32 << zoom) - 1;
+	let (x, y) = lonlat2tile(lon, lat, zoom);
+	Tile::new(x.min(max), y.min(max), zoom)
+}
+
+/// Check lon/lat coordinates against the Web Mercator bounds according to the policy.
+pub(crate) fn apply_policy(lon: f64, lat: f64, policy: LonLatPolicy) -> Result<(f64, f64), TileError> {
+	if !lon.is_finite() || !lat.is_finite() {
+		return Err(TileError::NonFinite { lon, lat });
+	}
+
+	match policy {
+		LonLatPolicy::Error => {
+			if !(-180f64..=180f64).contains(&lon) {
+				return Err(TileError::LongitudeOutOfRange(lon));
+			}
+			if !(-MAX_LATITUDE..=MAX_LATITUDE).contains(&lat) {
+				return Err(TileError::LatitudeOutOfRange(lat));
+			}
+			Ok((lon, lat))
+		}
+		LonLatPolicy::Clamp => Ok((lon.clamp(-180f64, 180f64), lat.clamp(-MAX_LATITUDE, MAX_LATITUDE))),
+		LonLatPolicy::Wrap => Ok(((lon + 180f64).rem_euclid(360f64) - 180f64, lat.clamp(-MAX_LATITUDE, MAX_LATITUDE))),
+	}
+}
+
 /// Convert a Web Mercator tile to lon/lat coordinates at a given zoom level.
 ///
 /// # Arguments
@@ -33,9 +153,23 @@ pub fn lonlat2tile(lon: f64, lat: f64, zoom: u8) -> (u32, u32) {
 /// * `y`    - Y tile coordinate
 /// * `zoom` - zoom level
 pub fn tile2lonlat(x: u32, y: u32, zoom: u8) -> (f64, f64) {
+	tile2lonlat_frac(x as f64, y as f64, zoom)
+}
+
+/// Convert fractional Web Mercator tile coordinates to lon/lat coordinates at a given zoom level.
+///
+/// This is the inverse of [`lonlat2tile_frac`], and returns the same result as [`tile2lonlat`]
+/// for integer coordinates.
+///
+/// # Arguments
+///
+/// * `x`    - fractional X tile coordinate
+/// * `y`    - fractional Y tile coordinate
+/// * `zoom` - zoom level
+pub fn tile2lonlat_frac(x: f64, y: f64, zoom: u8) -> (f64, f64) {
 	let z = 2f64.powf(zoom as f64);
-	let lon = x as f64 / z * 360f64 - 180f64;
-	let lat = (PI * (1f64 - 2f64 * y as f64 / z)).sinh().atan().to_degrees();
+	let lon = x / z * 360f64 - 180f64;
+	let lat = (PI * (1f64 - 2f64 * y / z)).sinh().atan().to_degrees();
 	(lon, lat)
 }
 
@@ -52,16 +186,35 @@ pub fn tile2lonlat(x: u32, y: u32, zoom: u8) -> (f64, f64) {
 /// +--------+--------+
 /// ```
 ///
+/// The coordinates overflow past zoom level 31, use [`checked_zoom_in`], [`Tile::zoom_in`]
+/// or [`Tile64::zoom_in`] for a checked version.
+///
 /// # Arguments
 ///
 /// * `x` - X tile coordinate
 /// * `y` - Y tile coordinate
+#[allow(clippy::type_complexity)]
 pub fn zoom_in(x: u32, y: u32) -> ((u32, u32), (u32, u32), (u32, u32), (u32, u32)) {
 	let x2 = 2 * x;
 	let y2 = 2 * y;
 	((x2, y2), (x2 + 1, y2), (x2, y2 + 1), (x2 + 1, y2 + 1))
 }
 
+/// Zoom in from the given tile, checking that the coordinates do not overflow.
+///
+/// Returns the same tiles as [`zoom_in`], or `None` if their coordinates do not fit in a `u32`.
+///
+/// # Arguments
+///
+/// * `x` - X tile coordinate
+/// * `y` - Y tile coordinate
+#[allow(clippy::type_complexity)]
+pub fn checked_zoom_in(x: u32, y: u32) -> Option<((u32, u32), (u32, u32), (u32, u32), (u32, u32))> {
+	let x2 = x.checked_mul(2)?;
+	let y2 = y.checked_mul(2)?;
+	Some(((x2, y2), (x2 + 1, y2), (x2, y2 + 1), (x2 + 1, y2 + 1)))
+}
+
 /// Zoom out from the given tile.
 ///
 /// The `zoom out` function returns the tile onto which the given tile is merged
@@ -93,9 +246,55 @@ mod tests {
 		assert_eq!(lonlat2tile(123456.789, 123456.789, 0), (343, 0));
 	}
 
+	#[test]
+	fn test_frac() {
+		assert_eq!(lonlat2tile_frac(0.0, 0.0, 0), (0.5, 0.5));
+		assert_eq!(lonlat2tile_frac(-90.0, 0.0, 2), (1.0, 2.0));
+		assert_eq!(tile2lonlat_frac(4376.0, 2932.0, 13), tile2lonlat(4376, 2932, 13));
+		assert_eq!(tile2lonlat_frac(0.5, 0.5, 0), (0.0, 0.0));
+
+		let (x, y) = lonlat2tile_frac(14.016667, 42.683333, 13);
+		assert_eq!((x as u32, y as u32), lonlat2tile(14.016667, 42.683333, 13));
+		let (lon, lat) = tile2lonlat_frac(x, y, 13);
+		assert!((lon - 14.016667).abs() < 1e-9 && (lat - 42.683333).abs() < 1e-9);
+
+		// Zooming in doubles fractional coordinates.
+		let (x2, y2) = lonlat2tile_frac(14.016667, 42.683333, 14);
+		assert!((x2 - 2.0 * x).abs() < 1e-9 && (y2 - 2.0 * y).abs() < 1e-9);
+	}
+
+	#[test]
+	fn test_try_l2t() {
+		let tile = |lon, lat, zoom, policy| try_lonlat2tile(lon, lat, zoom, policy).map(<(u32, u32, u8)>::from);
+
+		assert_eq!(tile(12.3046875, 45.460130637921, 13, LonLatPolicy::Error), Ok((4376, 2932, 13)));
+		assert_eq!(tile(180.0, -MAX_LATITUDE, 2, LonLatPolicy::Error), Ok((3, 3, 2)));
+		assert_eq!(tile(-180.0, MAX_LATITUDE, 2, LonLatPolicy::Error), Ok((0, 0, 2)));
+		assert_eq!(tile(181.0, 0.0, 2, LonLatPolicy::Error), Err(TileError::LongitudeOutOfRange(181.0)));
+		assert_eq!(tile(0.0, 90.0, 2, LonLatPolicy::Error), Err(TileError::LatitudeOutOfRange(90.0)));
+
+		assert_eq!(tile(0.0, 90.0, 2, LonLatPolicy::Clamp), Ok((2, 0, 2)));
+		assert_eq!(tile(0.0, -90.0, 2, LonLatPolicy::Clamp), Ok((2, 3, 2)));
+		assert_eq!(tile(200.0, 0.0, 2, LonLatPolicy::Clamp), Ok((3, 2, 2)));
+
+		assert_eq!(tile(200.0, 0.0, 2, LonLatPolicy::Wrap), Ok((0, 2, 2)));
+		assert_eq!(tile(-190.0, 0.0, 2, LonLatPolicy::Wrap), Ok((3, 2, 2)));
+		assert_eq!(tile(540.0, -90.0, 1, LonLatPolicy::Wrap), Ok((0, 1, 1)));
+
+		for policy in [LonLatPolicy::Error, LonLatPolicy::Clamp, LonLatPolicy::Wrap] {
+			assert!(matches!(tile(f64::NAN, 0.0, 2, policy), Err(TileError::NonFinite { .. })));
+			assert!(matches!(tile(0.0, f64::INFINITY, 2, policy), Err(TileError::NonFinite { .. })));
+		}
+		assert_eq!(tile(0.0, 0.0, 32, LonLatPolicy::Clamp), Err(TileError::InvalidZoom(32)));
+	}
+
 	#[test]
 	fn test_zoom() {
 		assert_eq!(zoom_in(1, 1), ((2, 2), (3, 2), (2, 3), (3, 3)));
+		assert_eq!(checked_zoom_in(1, 1), Some(zoom_in(1, 1)));
+		assert_eq!(checked_zoom_in(u32::MAX / 2, 0), Some(zoom_in(u32::MAX / 2, 0)));
+		assert_eq!(checked_zoom_in(1 << 31, 0), None);
+		assert_eq!(checked_zoom_in(0, u32::MAX), None);
 		assert_eq!(zoom_out(5, 7), (2, 3));
 		assert_eq!(zoom_out(0, 0), (0, 0));
 	}
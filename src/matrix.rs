@@ -0,0 +1,557 @@
+use std::collections::HashSet;
+use std::error::Error;
+use std::f64::consts::PI;
+use std::fmt;
+
+use crate::cover::{cell, cell_span, cover_path, cover_polygon};
+use crate::{
+	lonlat_to_meters, meters_to_lonlat, scale_denominator, Bounds, TileError, TileRange, EARTH_RADIUS, MAX_EXTENT,
+	MAX_LATITUDE, OGC_DPI, OGC_PIXEL_SIZE, TILE_SIZE,
+};
+
+/// Eccentricity of the WGS 84 ellipsoid, used by the World Mercator projection (EPSG:3395).
+const WGS84_ECCENTRICITY: f64 = 0.08181919084262149;
+
+/// Error returned when converting coordinates with a [`TileMatrixSet`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TileMatrixError {
+	/// The coordinate reference system of the set cannot be converted from lon/lat coordinates.
+	UnsupportedCrs,
+	/// The zoom level, coordinates or tile are not valid for the set.
+	Tile(TileError),
+}
+
+impl fmt::Display for TileMatrixError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			TileMatrixError::UnsupportedCrs => write!(f, "unsupported coordinate reference system"),
+			TileMatrixError::Tile(err) => err.fmt(f),
+		}
+	}
+}
+
+impl Error for TileMatrixError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			TileMatrixError::Tile(err) => Some(err),
+			TileMatrixError::UnsupportedCrs => None,
+		}
+	}
+}
+
+impl From<TileError> for TileMatrixError {
+	fn from(err: TileError) -> Self {
+		TileMatrixError::Tile(err)
+	}
+}
+
+/// Corner of a tile matrix from which its rows are numbered.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "camelCase"))]
+pub enum CornerOfOrigin {
+	/// Row 0 is at the top (north), as in the [`crate::Scheme::Xyz`] scheme.
+	#[default]
+	TopLeft,
+	/// Row 0 is at the bottom (south), as in the [`crate::Scheme::Tms`] scheme.
+	BottomLeft,
+}
+
+/// Grid of tiles at a single zoom level of a [`TileMatrixSet`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "camelCase"))]
+pub struct TileMatrix {
+	/// Identifier of the tile matrix, which is its zoom level in the sets provided by this crate.
+	pub id: String,
+	/// Scale denominator of the tile matrix, for the pixel size of [`OGC_PIXEL_SIZE`].
+	pub scale_denominator: f64,
+	/// Size of a pixel, in units of the coordinate reference system.
+	pub cell_size: f64,
+	/// Corner of the matrix at which tile 0 is located.
+	#[cfg_attr(feature = "serde", serde(default))]
+	pub corner_of_origin: CornerOfOrigin,
+	/// Position of the corner of origin, in units of the coordinate reference system.
+	pub point_of_origin: [f64; 2],
+	/// Width of a tile, in pixels.
+	pub tile_width: u32,
+	/// Height of a tile, in pixels.
+	pub tile_height: u32,
+	/// Number of columns of tiles.
+	pub matrix_width: u32,
+	/// Number of rows of tiles.
+	pub matrix_height: u32,
+}
+
+impl TileMatrix {
+	/// Convert coordinates of the coordinate reference system to fractional column and row coordinates.
+	///
+	/// # Arguments
+	///
+	/// * `x` - first coordinate, in units of the coordinate reference system
+	/// * `y` - second coordinate, in units of the coordinate reference system
+	pub fn crs_to_tile(&self, x: f64, y: f64) -> (f64, f64) {
+		let [origin_x, origin_y] = self.point_of_origin;
+		let col = (x - origin_x) / (self.cell_size * self.tile_width as f64);
+		let row = (y - origin_y) / (self.cell_size * self.tile_height as f64);
+		match self.corner_of_origin {
+			CornerOfOrigin::TopLeft => (col, -row),
+			CornerOfOrigin::BottomLeft => (col, row),
+		}
+	}
+
+	/// Convert fractional column and row coordinates to coordinates of the coordinate reference system.
+	///
+	/// This is the inverse of [`TileMatrix::crs_to_tile`].
+	///
+	/// # Arguments
+	///
+	/// * `col` - fractional column coordinate
+	/// * `row` - fractional row coordinate
+	pub fn tile_to_crs(&self, col: f64, row: f64) -> (f64, f64) {
+		let [origin_x, origin_y] = self.point_of_origin;
+		let row = match self.corner_of_origin {
+			CornerOfOrigin::TopLeft => -row,
+			CornerOfOrigin::BottomLeft => row,
+		};
+		let x = origin_x + col * self.cell_size * self.tile_width as f64;
+		let y = origin_y + row * self.cell_size * self.tile_height as f64;
+		(x, y)
+	}
+
+	/// Column and row of the last tile of the matrix.
+	fn last_cell(&self) -> (i64, i64) {
+		(self.matrix_width as i64 - 1, self.matrix_height as i64 - 1)
+	}
+}
+
+/// Tile grid following the [OGC Two Dimensional Tile Matrix Set](https://docs.ogc.org/is/17-083r4/17-083r4.html)
+/// standard, made of one [`TileMatrix`] per zoom level.
+///
+/// Lon/lat coordinates can only be converted for the coordinate reference systems of the sets
+/// provided by this crate: Web Mercator (EPSG:3857), World Mercator (EPSG:3395) and CRS84.
+/// The tile matrix at a given zoom level is the one at that index in `tile_matrices`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "camelCase"))]
+pub struct TileMatrixSet {
+	/// Identifier of the tile matrix set.
+	pub id: String,
+	/// Human-readable title.
+	#[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+	pub title: Option<String>,
+	/// URI of the tile matrix set in the OGC registry.
+	#[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+	pub uri: Option<String>,
+	/// URI of the coordinate reference system.
+	pub crs: String,
+	/// Names of the axes of the coordinate reference system, in the order of the coordinates.
+	#[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+	pub ordered_axes: Vec<String>,
+	/// Tile matrices, from the least to the most detailed.
+	pub tile_matrices: Vec<TileMatrix>,
+}
+
+impl TileMatrixSet {
+	/// The `WebMercatorQuad` set used by the other functions of this crate, with zoom levels 0 to 24.
+	pub fn web_mercator_quad() -> Self {
+		Self {
+			id: "WebMercatorQuad".to_string(),
+			title: Some("Google Maps Compatible for the World".to_string()),
+			uri: Some("http://www.opengis.net/def/tilematrixset/OGC/1.0/WebMercatorQuad".to_string()),
+			crs: "http://www.opengis.net/def/crs/EPSG/0/3857".to_string(),
+			ordered_axes: vec!["X".to_string(), "Y".to_string()],
+			tile_matrices: (0..=24).map(mercator_matrix).collect(),
+		}
+	}
+
+	/// The `WorldCRS84Quad` set in lon/lat coordinates, with 2x1 tiles at zoom level 0 and zoom levels 0 to 23.
+	pub fn world_crs84_quad() -> Self {
+		Self {
+			id: "WorldCRS84Quad".to_string(),
+			title: Some("CRS84 for the World".to_string()),
+			uri: Some("http://www.opengis.net/def/tilematrixset/OGC/1.0/WorldCRS84Quad".to_string()),
+			crs: "http://www.opengis.net/def/crs/OGC/1.3/CRS84".to_string(),
+			ordered_axes: vec!["Lon".to_string(), "Lat".to_string()],
+			tile_matrices: (0..=23)
+				.map(|zoom| {
+					let n = 1u32 << zoom;
+					let cell_size = 180f64 / (TILE_SIZE as f64 * n as f64);
+					TileMatrix {
+						id: zoom.to_string(),
+						scale_denominator: cell_size * PI * EARTH_RADIUS / 180f64 / OGC_PIXEL_SIZE,
+						cell_size,
+						corner_of_origin: CornerOfOrigin::TopLeft,
+						point_of_origin: [-180f64, 90f64],
+						tile_width: TILE_SIZE,
+						tile_height: TILE_SIZE,
+						matrix_width: 2 * n,
+						matrix_height: n,
+					}
+				})
+				.collect(),
+		}
+	}
+
+	/// The `WorldMercatorWGS84Quad` set in World Mercator (EPSG:3395) coordinates, with zoom levels 0 to 24.
+	pub fn world_mercator_wgs84_quad() -> Self {
+		Self {
+			id: "WorldMercatorWGS84Quad".to_string(),
+			title: Some("World Mercator WGS84 (ellipsoid)".to_string()),
+			uri: Some("http://www.opengis.net/def/tilematrixset/OGC/1.0/WorldMercatorWGS84Quad".to_string()),
+			crs: "http://www.opengis.net/def/crs/EPSG/0/3395".to_string(),
+			ordered_axes: vec!["E".to_string(), "N".to_string()],
+			tile_matrices: (0..=24).map(mercator_matrix).collect(),
+		}
+	}
+
+	/// Tile matrix at a given zoom level.
+	pub fn tile_matrix(&self, zoom: u8) -> Option<&TileMatrix> {
+		self.tile_matrices.get(zoom as usize)
+	}
+
+	/// Convert lon/lat coordinates to fractional column and row coordinates at a given zoom level.
+	///
+	/// Latitudes are clamped to the extent of the projection.
+	///
+	/// # Arguments
+	///
+	/// * `lon`  - longitude coordinate (W-E), in degrees
+	/// * `lat`  - latitude  coordinate (N-S), in degrees
+	/// * `zoom` - zoom level
+	pub fn lonlat2tile_frac(&self, lon: f64, lat: f64, zoom: u8) -> Result<(f64, f64), TileMatrixError> {
+		let (matrix, projection) = self.grid(zoom)?;
+		let (x, y) = projection.project(lon, lat)?;
+		Ok(matrix.crs_to_tile(x, y))
+	}
+
+	/// Find the column and row of the tile containing the given lon/lat coordinates at a given zoom level.
+	///
+	/// Latitudes are clamped to the extent of the projection, and the tile to the tile matrix.
+	///
+	/// # Arguments
+	///
+	/// * `lon`  - longitude coordinate (W-E), in degrees
+	/// * `lat`  - latitude  coordinate (N-S), in degrees
+	/// * `zoom` - zoom level
+	pub fn lonlat2tile(&self, lon: f64, lat: f64, zoom: u8) -> Result<(u32, u32), TileMatrixError> {
+		let (matrix, projection) = self.grid(zoom)?;
+		let (x, y) = projection.project(lon, lat)?;
+		let (col, row) = matrix.crs_to_tile(x, y);
+		let (max_col, max_row) = matrix.last_cell();
+		Ok((cell(col, max_col) as u32, cell(row, max_row) as u32))
+	}
+
+	/// Lon/lat coordinates of the corner of a tile closest to the corner of origin.
+	///
+	/// # Arguments
+	///
+	/// * `col`  - column of the tile
+	/// * `row`  - row of the tile
+	/// * `zoom` - zoom level
+	pub fn tile2lonlat(&self, col: u32, row: u32, zoom: u8) -> Result<(f64, f64), TileMatrixError> {
+		let (matrix, projection) = self.grid(zoom)?;
+		let (x, y) = matrix.tile_to_crs(col as f64, row as f64);
+		Ok(projection.unproject(x, y))
+	}
+
+	/// Bounding box of a tile in lon/lat coordinates, in degrees.
+	///
+	/// # Arguments
+	///
+	/// * `col`  - column of the tile
+	/// * `row`  - row of the tile
+	/// * `zoom` - zoom level
+	pub fn tile_bounds(&self, col: u32, row: u32, zoom: u8) -> Result<Bounds, TileMatrixError> {
+		let (matrix, projection) = self.grid(zoom)?;
+		if col >= matrix.matrix_width || row >= matrix.matrix_height {
+			return Err(TileError::OutOfRange { x: col as u64, y: row as u64, z: zoom }.into());
+		}
+		let (x0, y0) = matrix.tile_to_crs(col as f64, row as f64);
+		let (x1, y1) = matrix.tile_to_crs(col as f64 + 1f64, row as f64 + 1f64);
+		let (west, south) = projection.unproject(x0.min(x1), y0.min(y1));
+		let (east, north) = projection.unproject(x0.max(x1), y0.max(y1));
+		Ok(Bounds { west, south, east, north })
+	}
+
+	/// Find the tiles covering a lon/lat bounding box at a given zoom level.
+	///
+	/// As with [`TileRange::from_bounds`], edges lying exactly on a tile boundary do not include
+	/// the tiles beyond that boundary. Boxes crossing the antimeridian are not supported.
+	///
+	/// # Arguments
+	///
+	/// * `bounds` - bounding box, in degrees
+	/// * `zoom`   - zoom level
+	pub fn tile_range(&self, bounds: &Bounds, zoom: u8) -> Result<TileMatrixLimits, TileMatrixError> {
+		let (matrix, projection) = self.grid(zoom)?;
+		let Bounds { west, south, east, north } = *bounds;
+		let (x0, y0) = projection.project(west, north)?;
+		let (x1, y1) = projection.project(east, south)?;
+		if west > east || south > north {
+			return Err(TileError::InvalidRange.into());
+		}
+
+		let (col0, row0) = matrix.crs_to_tile(x0, y0);
+		let (col1, row1) = matrix.crs_to_tile(x1, y1);
+		let (max_col, max_row) = matrix.last_cell();
+		let (min_tile_col, max_tile_col) = cell_span(col0, col1, max_col);
+		let (min_tile_row, max_tile_row) = cell_span(row0.min(row1), row0.max(row1), max_row);
+		Ok(TileMatrixLimits { tile_matrix: matrix.id.clone(), min_tile_row, max_tile_row, min_tile_col, max_tile_col })
+	}
+
+	/// Find the tiles crossed by a lon/lat line string at a given zoom level, as with [`crate::line_tiles`].
+	///
+	/// The tiles are returned as `(col, row)` coordinates in row-major order.
+	///
+	/// # Arguments
+	///
+	/// * `line` - lon/lat coordinates, in degrees
+	/// * `zoom` - zoom level
+	pub fn line_tiles(&self, line: &[(f64, f64)], zoom: u8) -> Result<Vec<(u32, u32)>, TileMatrixError> {
+		let (matrix, _) = self.grid(zoom)?;
+		let mut tiles = HashSet::new();
+		cover_path(&self.project(line, zoom)?, matrix.last_cell(), &mut tiles);
+		Ok(sorted(tiles))
+	}
+
+	/// Find the tiles intersecting a lon/lat polygon at a given zoom level, as with [`crate::polygon_tiles`].
+	///
+	/// The tiles are returned as `(col, row)` coordinates in row-major order.
+	///
+	/// # Arguments
+	///
+	/// * `polygon` - rings of lon/lat coordinates, in degrees
+	/// * `zoom`    - zoom level
+	pub fn polygon_tiles(&self, polygon: &[Vec<(f64, f64)>], zoom: u8) -> Result<Vec<(u32, u32)>, TileMatrixError> {
+		let (matrix, _) = self.grid(zoom)?;
+		let rings = polygon.iter().map(|ring| self.project(ring, zoom)).collect::<Result<Vec<_>, _>>()?;
+		let mut tiles = HashSet::new();
+		cover_polygon(&rings, matrix.last_cell(), &mut tiles);
+		Ok(sorted(tiles))
+	}
+
+	/// Parse a tile matrix set from its OGC JSON encoding.
+	#[cfg(feature = "serde")]
+	pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+		serde_json::from_str(json)
+	}
+
+	/// Generate the OGC JSON encoding of the tile matrix set.
+	#[cfg(feature = "serde")]
+	pub fn to_json(&self) -> String {
+		serde_json::to_string(self).expect("tile matrix sets only have string keys")
+	}
+
+	fn grid(&self, zoom: u8) -> Result<(&TileMatrix, Projection), TileMatrixError> {
+		let matrix = self.tile_matrix(zoom).ok_or(TileError::InvalidZoom(zoom))?;
+		let projection = Projection::from_crs(&self.crs).ok_or(TileMatrixError::UnsupportedCrs)?;
+		Ok((matrix, projection))
+	}
+
+	fn project(&self, points: &[(f64, f64)], zoom: u8) -> Result<Vec<(f64, f64)>, TileMatrixError> {
+		points.iter().map(|&(lon, lat)| self.lonlat2tile_frac(lon, lat, zoom)).collect()
+	}
+}
+
+/// Tile matrix of a Web Mercator or World Mercator set, made of `2^zoom` by `2^zoom` tiles.
+fn mercator_matrix(zoom: u8) -> TileMatrix {
+	let n = 1u32 << zoom;
+	TileMatrix {
+		id: zoom.to_string(),
+		scale_denominator: scale_denominator(0f64, zoom, TILE_SIZE, OGC_DPI),
+		cell_size: 2f64 * MAX_EXTENT / (TILE_SIZE as f64 * n as f64),
+		corner_of_origin: CornerOfOrigin::TopLeft,
+		point_of_origin: [-MAX_EXTENT, MAX_EXTENT],
+		tile_width: TILE_SIZE,
+		tile_height: TILE_SIZE,
+		matrix_width: n,
+		matrix_height: n,
+	}
+}
+
+/// Convert a set of `(col, row)` coordinates to a list in row-major order.
+fn sorted(tiles: HashSet<(u32, u32)>) -> Vec<(u32, u32)> {
+	let mut tiles: Vec<_> = tiles.into_iter().collect();
+	tiles.sort_unstable_by_key(|&(col, row)| (row, col));
+	tiles
+}
+
+/// Range of tiles of a [`TileMatrix`], as in the OGC `TileMatrixLimits` encoding, with inclusive bounds.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "camelCase"))]
+pub struct TileMatrixLimits {
+	/// Identifier of the tile matrix.
+	pub tile_matrix: String,
+	/// Row of the first tile.
+	pub min_tile_row: u32,
+	/// Row of the last tile.
+	pub max_tile_row: u32,
+	/// Column of the first tile.
+	pub min_tile_col: u32,
+	/// Column of the last tile.
+	pub max_tile_col: u32,
+}
+
+impl TileMatrixLimits {
+	/// Iterate over the `(col, row)` coordinates of the tiles in row-major order.
+	pub fn iter(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+		let cols = self.min_tile_col..=self.max_tile_col;
+		(self.min_tile_row..=self.max_tile_row).flat_map(move |row| cols.clone().map(move |col| (col, row)))
+	}
+}
+
+/// Limits of a range of the `WebMercatorQuad` set.
+impl From<TileRange> for TileMatrixLimits {
+	fn from(range: TileRange) -> Self {
+		Self {
+			tile_matrix: range.zoom().to_string(),
+			min_tile_row: range.min_y(),
+			max_tile_row: range.max_y(),
+			min_tile_col: range.min_x(),
+			max_tile_col: range.max_x(),
+		}
+	}
+}
+
+/// Projection of lon/lat coordinates to a supported coordinate reference system.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Projection {
+	/// Web Mercator (EPSG:3857), in meters.
+	WebMercator,
+	/// World Mercator on the WGS 84 ellipsoid (EPSG:3395), in meters.
+	WorldMercator,
+	/// Longitude and latitude (CRS84), in degrees.
+	LonLat,
+}
+
+impl Projection {
+	fn from_crs(crs: &str) -> Option<Self> {
+		let crs = crs.trim_start_matches("http://").trim_start_matches("https://");
+		match crs.trim_start_matches("www.opengis.net/def/crs/") {
+			"EPSG/0/3857" | "EPSG:3857" => Some(Self::WebMercator),
+			"EPSG/0/3395" | "EPSG:3395" => Some(Self::WorldMercator),
+			"OGC/1.3/CRS84" | "OGC/0/CRS84" | "OGC:CRS84" => Some(Self::LonLat),
+			_ => None,
+		}
+	}
+
+	fn project(&self, lon: f64, lat: f64) -> Result<(f64, f64), TileError> {
+		if !lon.is_finite() || !lat.is_finite() {
+			return Err(TileError::NonFinite { lon, lat });
+		}
+		if !(-180f64..=180f64).contains(&lon) {
+			return Err(TileError::LongitudeOutOfRange(lon));
+		}
+		Ok(match self {
+			Self::WebMercator => lonlat_to_meters(lon, lat.clamp(-MAX_LATITUDE, MAX_LATITUDE)),
+			Self::WorldMercator => {
+				let (sin, e) = (lat.clamp(-90f64, 90f64).to_radians().sin(), WGS84_ECCENTRICITY);
+				let y = EARTH_RADIUS * (sin.atanh() - e * (e * sin).atanh());
+				(lon / 180f64 * MAX_EXTENT, y.clamp(-MAX_EXTENT, MAX_EXTENT))
+			}
+			Self::LonLat => (lon, lat.clamp(-90f64, 90f64)),
+		})
+	}
+
+	fn unproject(&self, x: f64, y: f64) -> (f64, f64) {
+		match self {
+			Self::WebMercator => meters_to_lonlat(x, y),
+			Self::WorldMercator => {
+				// The latitude has no closed form, and is found by fixed-point iteration.
+				let (t, e) = ((-y / EARTH_RADIUS).exp(), WGS84_ECCENTRICITY);
+				let mut lat = PI / 2f64 - 2f64 * t.atan();
+				for _ in 0..16 {
+					let sin = e * lat.sin();
+					lat = PI / 2f64 - 2f64 * (t * ((1f64 - sin) / (1f64 + sin)).powf(e / 2f64)).atan();
+				}
+				(x / MAX_EXTENT * 180f64, lat.to_degrees())
+			}
+			Self::LonLat => (x, y),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{lonlat2tile_frac, tile_bounds};
+
+	fn assert_close(a: (f64, f64), b: (f64, f64)) {
+		assert!((a.0 - b.0).abs() < 1e-6 && (a.1 - b.1).abs() < 1e-6, "{a:?} != {b:?}");
+	}
+
+	#[test]
+	fn test_web_mercator_quad() {
+		let tms = TileMatrixSet::web_mercator_quad();
+		let matrix = tms.tile_matrix(0).unwrap();
+		assert!((matrix.scale_denominator - 559082264.0287178).abs() < 1e-6);
+		assert!((matrix.cell_size - 156543.03392804097).abs() < 1e-9);
+
+		for (lon, lat) in [(12.3, 45.4), (-170.0, -80.0), (0.1, 0.1)] {
+			assert_close(tms.lonlat2tile_frac(lon, lat, 13).unwrap(), lonlat2tile_frac(lon, lat, 13));
+		}
+		assert_eq!(tms.lonlat2tile(12.31, 45.45, 13), Ok((4376, 2932)));
+		let bounds = tms.tile_bounds(4376, 2932, 13).unwrap();
+		let expected = tile_bounds(4376, 2932, 13);
+		assert_close((bounds.west, bounds.south), (expected.west, expected.south));
+		assert_close((bounds.east, bounds.north), (expected.east, expected.north));
+
+		let bounds = Bounds::new(-10.0, -10.0, 10.0, 10.0);
+		let expected = TileRange::from_bounds(&bounds, 5).unwrap()[0];
+		assert_eq!(tms.tile_range(&bounds, 5), Ok(expected.into()));
+		assert_eq!(tms.tile_range(&bounds, 25), Err(TileMatrixError::Tile(TileError::InvalidZoom(25))));
+	}
+
+	#[test]
+	fn test_world_crs84_quad() {
+		let tms = TileMatrixSet::world_crs84_quad();
+		let matrix = tms.tile_matrix(0).unwrap();
+		assert_eq!((matrix.matrix_width, matrix.matrix_height), (2, 1));
+		assert!((matrix.scale_denominator - 279541132.0143589).abs() < 1e-6);
+
+		assert_eq!(tms.lonlat2tile(-90.0, 45.0, 0), Ok((0, 0)));
+		assert_eq!(tms.lonlat2tile(90.0, -45.0, 0), Ok((1, 0)));
+		assert_eq!(tms.lonlat2tile(180.0, -90.0, 1), Ok((3, 1)));
+		assert_eq!(tms.tile_bounds(1, 0, 1), Ok(Bounds::new(-90.0, 0.0, 0.0, 90.0)));
+		assert!(tms.tile_bounds(4, 0, 1).is_err());
+
+		let limits = tms.tile_range(&Bounds::new(-90.0, 0.0, 90.0, 90.0), 1).unwrap();
+		assert_eq!(limits.iter().collect::<Vec<_>>(), [(1, 0), (2, 0)]);
+		let line = tms.line_tiles(&[(-135.0, 45.0), (135.0, -60.0)], 1).unwrap();
+		assert_eq!(line, [(0, 0), (1, 0), (1, 1), (2, 1), (3, 1)]);
+		let square = vec![(-100.0, -10.0), (100.0, -10.0), (100.0, 10.0), (-100.0, 10.0)];
+		let tiles = tms.polygon_tiles(&[square], 1).unwrap();
+		assert_eq!(tiles, [(0, 0), (1, 0), (2, 0), (3, 0), (0, 1), (1, 1), (2, 1), (3, 1)]);
+	}
+
+	#[test]
+	fn test_world_mercator_wgs84_quad() {
+		let tms = TileMatrixSet::world_mercator_wgs84_quad();
+		for (lon, lat) in [(12.3, 45.4), (-170.0, -80.0), (0.0, 0.0)] {
+			let (col, row) = tms.lonlat2tile_frac(lon, lat, 10).unwrap();
+			let matrix = tms.tile_matrix(10).unwrap();
+			let (x, y) = matrix.tile_to_crs(col, row);
+			assert_close(Projection::WorldMercator.unproject(x, y), (lon, lat));
+		}
+		// The ellipsoid flattens the poles, moving latitudes towards the equator compared to Web Mercator.
+		let (_, row) = tms.lonlat2tile_frac(0.0, 45.0, 0).unwrap();
+		assert!(row > lonlat2tile_frac(0.0, 45.0, 0).1);
+
+		let mut custom = tms.clone();
+		custom.crs = "EPSG:4326".to_string();
+		assert_eq!(custom.lonlat2tile(0.0, 0.0, 0), Err(TileMatrixError::UnsupportedCrs));
+	}
+
+	#[cfg(feature = "serde")]
+	#[test]
+	fn test_json() {
+		let tms = TileMatrixSet::world_crs84_quad();
+		let json = tms.to_json();
+		assert!(json.starts_with(r#"{"id":"WorldCRS84Quad","title":"CRS84 for the World","uri":"#));
+		assert!(json.contains(r#"{"id":"0","scaleDenominator":279541132.0143588,"cellSize":0.703125,"#));
+		assert!(json.contains(r#""cornerOfOrigin":"topLeft","pointOfOrigin":[-180.0,90.0],"tileWidth":256,"#));
+		assert_eq!(TileMatrixSet::from_json(&json).unwrap(), tms);
+
+		let limits = TileMatrixLimits::from(TileRange::new(1, 2, 3, 4, 5).unwrap());
+		let json = r#"{"tileMatrix":"5","minTileRow":2,"maxTileRow":4,"minTileCol":1,"maxTileCol":3}"#;
+		assert_eq!(serde_json::to_string(&limits).unwrap(), json);
+	}
+}
@@ -0,0 +1,298 @@
+//! Support for [MBTiles 1.3](https://github.com/mapbox/mbtiles-spec/blob/master/1.3/spec.md) archives.
+//!
+//! MBTiles archives number their rows with the [`Scheme::Tms`] scheme. The conversion
+//! from and to the XYZ rows of [`Tile`] is handled transparently.
+
+use std::error;
+use std::fmt;
+use std::path::Path;
+
+use rusqlite::{params, Connection, OpenFlags, OptionalExtension, Transaction};
+
+use crate::{Scheme, Tile, TileError};
+
+/// Layout of the tables of an MBTiles archive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Schema {
+	/// Tile data stored directly in the `tiles` table.
+	Flat,
+	/// Tile data stored once in the `images` table, referenced by the `map` table,
+	/// with a `tiles` view joining both.
+	Deduplicated,
+}
+
+/// Error returned when reading or writing an archive fails.
+#[derive(Debug)]
+pub enum Error {
+	/// The SQLite database could not be read or written.
+	Sqlite(rusqlite::Error),
+	/// The archive contains a tile with invalid coordinates.
+	InvalidTile(TileError),
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Error::Sqlite(err) => write!(f, "SQLite error: {err}"),
+			Error::InvalidTile(err) => write!(f, "invalid tile: {err}"),
+		}
+	}
+}
+
+impl error::Error for Error {
+	fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+		match self {
+			Error::Sqlite(err) => Some(err),
+			Error::InvalidTile(err) => Some(err),
+		}
+	}
+}
+
+impl From<rusqlite::Error> for Error {
+	fn from(err: rusqlite::Error) -> Self {
+		Error::Sqlite(err)
+	}
+}
+
+impl From<TileError> for Error {
+	fn from(err: TileError) -> Self {
+		Error::InvalidTile(err)
+	}
+}
+
+const FLAT_SCHEMA: &str = "
+	CREATE TABLE IF NOT EXISTS metadata (name TEXT, value TEXT);
+	CREATE UNIQUE INDEX IF NOT EXISTS name ON metadata (name);
+	CREATE TABLE IF NOT EXISTS tiles (zoom_level INTEGER, tile_column INTEGER, tile_row INTEGER, tile_data BLOB);
+	CREATE UNIQUE INDEX IF NOT EXISTS tile_index ON tiles (zoom_level, tile_column, tile_row);
+";
+
+const DEDUPLICATED_SCHEMA: &str = "
+	CREATE TABLE IF NOT EXISTS metadata (name TEXT, value TEXT);
+	CREATE UNIQUE INDEX IF NOT EXISTS name ON metadata (name);
+	CREATE TABLE IF NOT EXISTS map (zoom_level INTEGER, tile_column INTEGER, tile_row INTEGER, tile_id TEXT);
+	CREATE UNIQUE INDEX IF NOT EXISTS map_index ON map (zoom_level, tile_column, tile_row);
+	CREATE TABLE IF NOT EXISTS images (tile_data BLOB, tile_id TEXT);
+	CREATE UNIQUE INDEX IF NOT EXISTS images_id ON images (tile_id);
+	CREATE VIEW IF NOT EXISTS tiles AS
+		SELECT map.zoom_level AS zoom_level, map.tile_column AS tile_column, map.tile_row AS tile_row,
+			images.tile_data AS tile_data
+		FROM map JOIN images ON images.tile_id = map.tile_id;
+";
+
+/// MBTiles archive backed by an SQLite database.
+#[derive(Debug)]
+pub struct MbTiles {
+	conn: Connection,
+	schema: Schema,
+}
+
+impl MbTiles {
+	/// Open an existing archive, detecting its schema.
+	///
+	/// Fails if the file does not exist, use [`MbTiles::create`] to create a new archive.
+	pub fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+		let flags = OpenFlags::SQLITE_OPEN_READ_WRITE | OpenFlags::SQLITE_OPEN_URI | OpenFlags::SQLITE_OPEN_NO_MUTEX;
+		Self::from_connection(Connection::open_with_flags(path, flags)?)
+	}
+
+	/// Create an archive with the given schema, or open it if it already exists.
+	pub fn create(path: impl AsRef<Path>, schema: Schema) -> Result<Self, Error> {
+		Self::init(Connection::open(path)?, schema)
+	}
+
+	/// Use an open database as an archive, detecting its schema.
+	pub fn from_connection(conn: Connection) -> Result<Self, Error> {
+		let tables: i64 = conn.query_row(
+			"SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name IN ('map', 'images')",
+			[],
+			|row| row.get(0),
+		)?;
+		let schema = if tables == 2 { Schema::Deduplicated } else { Schema::Flat };
+		Ok(Self { conn, schema })
+	}
+
+	/// Create the tables of the given schema in an open database, if they do not exist yet.
+	pub fn init(conn: Connection, schema: Schema) -> Result<Self, Error> {
+		conn.execute_batch(match schema {
+			Schema::Flat => FLAT_SCHEMA,
+			Schema::Deduplicated => DEDUPLICATED_SCHEMA,
+		})?;
+		Ok(Self { conn, schema })
+	}
+
+	/// Schema of the archive.
+	pub fn schema(&self) -> Schema {
+		self.schema
+	}
+
+	/// Give back the underlying database.
+	pub fn into_inner(self) -> Connection {
+		self.conn
+	}
+
+	/// Value of a metadata entry, such as `name`, `format` or `bounds`.
+	pub fn metadata(&self, name: &str) -> Result<Option<String>, Error> {
+		let query = "SELECT value FROM metadata WHERE name = ?1";
+		Ok(self.conn.query_row(query, [name], |row| row.get(0)).optional()?)
+	}
+
+	/// All metadata entries, sorted by name.
+	pub fn all_metadata(&self) -> Result<Vec<(String, String)>, Error> {
+		let mut stmt = self.conn.prepare("SELECT name, value FROM metadata ORDER BY name")?;
+		let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+		Ok(rows.collect::<Result<_, _>>()?)
+	}
+
+	/// Set a metadata entry, replacing any previous value.
+	pub fn set_metadata(&mut self, name: &str, value: &str) -> Result<(), Error> {
+		self.conn.execute("INSERT OR REPLACE INTO metadata (name, value) VALUES (?1, ?2)", [name, value])?;
+		Ok(())
+	}
+
+	/// Data of a tile, or `None` if the archive does not contain the tile.
+	pub fn get_tile(&self, tile: Tile) -> Result<Option<Vec<u8>>, Error> {
+		let data = self
+			.conn
+			.query_row(
+				"SELECT tile_data FROM tiles WHERE zoom_level = ?1 AND tile_column = ?2 AND tile_row = ?3",
+				params![tile.z(), tile.x(), tile.y_in(Scheme::Tms)],
+				|row| row.get(0),
+			)
+			.optional()?;
+		Ok(data)
+	}
+
+	/// All tiles of the archive, ordered by zoom level, column and row.
+	pub fn tiles(&self) -> Result<Vec<Tile>, Error> {
+		let mut stmt = self
+			.conn
+			.prepare("SELECT zoom_level, tile_column, tile_row FROM tiles ORDER BY zoom_level, tile_column, tile_row")?;
+		let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?;
+		rows.map(|row| {
+			let (z, x, y) = row?;
+			Ok(Tile::with_scheme(x, y, z, Scheme::Tms)?)
+		})
+		.collect()
+	}
+
+	/// Add a tile to the archive, replacing any previous data for the same tile.
+	pub fn put_tile(&mut self, tile: Tile, data: &[u8]) -> Result<(), Error> {
+		self.put_tiles([(tile, data)])
+	}
+
+	/// Add several tiles to the archive in a single transaction.
+	///
+	/// This is much faster than calling [`MbTiles::put_tile`] for every tile.
+	pub fn put_tiles<D: AsRef<[u8]>>(&mut self, tiles: impl IntoIterator<Item = (Tile, D)>) -> Result<(), Error> {
+		let schema = self.schema;
+		let tx = self.conn.transaction()?;
+		for (tile, data) in tiles {
+			let (z, x, y) = (tile.z(), tile.x(), tile.y_in(Scheme::Tms));
+			match schema {
+				Schema::Flat => {
+					tx.execute(
+						"INSERT OR REPLACE INTO tiles (zoom_level, tile_column, tile_row, tile_data) VALUES (?1, ?2, ?3, ?4)",
+						params![z, x, y, data.as_ref()],
+					)?;
+				}
+				Schema::Deduplicated => {
+					let id = insert_image(&tx, data.as_ref())?;
+					tx.execute(
+						"INSERT OR REPLACE INTO map (zoom_level, tile_column, tile_row, tile_id) VALUES (?1, ?2, ?3, ?4)",
+						params![z, x, y, id],
+					)?;
+				}
+			}
+		}
+		tx.commit()?;
+		Ok(())
+	}
+}
+
+/// Store tile data in the `images` table unless it is already there, and return its ID.
+fn insert_image(tx: &Transaction, data: &[u8]) -> Result<String, Error> {
+	let hash = fnv1a(data);
+	let mut suffix = 0u64;
+	loop {
+		let id = if suffix == 0 { format!("{hash:016x}") } else { format!("{hash:016x}-{suffix}") };
+		let existing: Option<Vec<u8>> =
+			tx.query_row("SELECT tile_data FROM images WHERE tile_id = ?1", [&id], |row| row.get(0)).optional()?;
+		match existing {
+			Some(existing) if existing == data => return Ok(id),
+			Some(_) => suffix += 1,
+			None => {
+				tx.execute("INSERT INTO images (tile_data, tile_id) VALUES (?1, ?2)", params![data, id])?;
+				return Ok(id);
+			}
+		}
+	}
+}
+
+/// 64-bit FNV-1a hash, which unlike the standard library hasher is stable across builds.
+fn fnv1a(data: &[u8]) -> u64 {
+	data.iter().fold(0xcbf29ce484222325, |hash, &byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_flat() {
+		let mut mbtiles = MbTiles::init(Connection::open_in_memory().unwrap(), Schema::Flat).unwrap();
+		let tile = Tile::new(4376, 2932, 13).unwrap();
+		mbtiles.put_tile(tile, b"tile").unwrap();
+		assert_eq!(mbtiles.get_tile(tile).unwrap().as_deref(), Some(&b"tile"[..]));
+		assert_eq!(mbtiles.get_tile(Tile::new(4376, 5259, 13).unwrap()).unwrap(), None);
+
+		// Rows are stored in the TMS scheme.
+		let row: u32 = mbtiles.conn.query_row("SELECT tile_row FROM tiles", [], |row| row.get(0)).unwrap();
+		assert_eq!(row, 5259);
+		assert_eq!(mbtiles.tiles().unwrap(), [tile]);
+	}
+
+	#[test]
+	fn test_deduplicated() {
+		let mut mbtiles = MbTiles::init(Connection::open_in_memory().unwrap(), Schema::Deduplicated).unwrap();
+		let [a, b, c] = [(0, 0), (1, 0), (0, 1)].map(|(x, y)| Tile::new(x, y, 1).unwrap());
+		mbtiles.put_tiles([(a, b"water"), (b, b"water"), (c, b"land!")]).unwrap();
+		mbtiles.put_tile(a, b"land!").unwrap();
+
+		let images: i64 = mbtiles.conn.query_row("SELECT COUNT(*) FROM images", [], |row| row.get(0)).unwrap();
+		assert_eq!(images, 2);
+		assert_eq!(mbtiles.get_tile(a).unwrap().as_deref(), Some(&b"land!"[..]));
+		assert_eq!(mbtiles.get_tile(b).unwrap().as_deref(), Some(&b"water"[..]));
+		assert_eq!(mbtiles.tiles().unwrap(), [c, a, b]);
+
+		let reopened = MbTiles::from_connection(mbtiles.into_inner()).unwrap();
+		assert_eq!(reopened.schema(), Schema::Deduplicated);
+	}
+
+	#[test]
+	fn test_metadata() {
+		let mut mbtiles = MbTiles::init(Connection::open_in_memory().unwrap(), Schema::Flat).unwrap();
+		mbtiles.set_metadata("name", "test").unwrap();
+		mbtiles.set_metadata("format", "jpg").unwrap();
+		mbtiles.set_metadata("format", "png").unwrap();
+		assert_eq!(mbtiles.metadata("format").unwrap().as_deref(), Some("png"));
+		assert_eq!(mbtiles.metadata("bounds").unwrap(), None);
+		let all = [("format".into(), "png".into()), ("name".into(), "test".into())];
+		assert_eq!(mbtiles.all_metadata().unwrap(), all);
+		assert_eq!(MbTiles::from_connection(mbtiles.into_inner()).unwrap().schema(), Schema::Flat);
+	}
+
+	#[test]
+	fn test_invalid_tile() {
+		let mbtiles = MbTiles::init(Connection::open_in_memory().unwrap(), Schema::Flat).unwrap();
+		mbtiles.conn.execute("INSERT INTO tiles VALUES (1, 0, 2, x'00')", []).unwrap();
+		assert!(matches!(mbtiles.tiles(), Err(Error::InvalidTile(_))));
+	}
+
+	#[test]
+	fn test_open_missing() {
+		let path = std::env::temp_dir().join(format!("webmercator_tiles_missing_{}.mbtiles", std::process::id()));
+		assert!(matches!(MbTiles::open(&path), Err(Error::Sqlite(_))));
+		assert!(!path.exists());
+	}
+}
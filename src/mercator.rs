@@ -0,0 +1,118 @@
+use std::f64::consts::PI;
+
+use crate::lonlat2tile;
+
+/// Radius of the sphere used by the Web Mercator projection (EPSG:3857), in meters.
+pub const EARTH_RADIUS: f64 = 6378137f64;
+
+/// Half the width of the Web Mercator map, in meters.
+///
+/// Projected coordinates range from `-MAX_EXTENT` to `MAX_EXTENT` on both axes.
+pub const MAX_EXTENT: f64 = PI * EARTH_RADIUS;
+
+/// Project lon/lat coordinates to Web Mercator (EPSG:3857) coordinates.
+///
+/// # Arguments
+///
+/// * `lon` - longitude coordinate (W-E), in degrees
+/// * `lat` - latitude  coordinate (N-S), in degrees
+pub fn lonlat_to_meters(lon: f64, lat: f64) -> (f64, f64) {
+	let x = lon / 180f64 * MAX_EXTENT;
+	let y = EARTH_RADIUS * lat.to_radians().tan().asinh();
+	(x, y)
+}
+
+/// Unproject Web Mercator (EPSG:3857) coordinates to lon/lat coordinates.
+///
+/// # Arguments
+///
+/// * `mx` - X coordinate (W-E), in meters
+/// * `my` - Y coordinate (S-N), in meters
+pub fn meters_to_lonlat(mx: f64, my: f64) -> (f64, f64) {
+	let lon = mx / MAX_EXTENT * 180f64;
+	let lat = (my / EARTH_RADIUS).sinh().atan().to_degrees();
+	(lon, lat)
+}
+
+/// Convert Web Mercator (EPSG:3857) coordinates to the tile containing them at a given zoom level.
+///
+/// The coordinates are unprojected and converted with [`lonlat2tile`], so both agree on which
+/// tile a point on a boundary belongs to.
+///
+/// # Arguments
+///
+/// * `mx`   - X coordinate (W-E), in meters
+/// * `my`   - Y coordinate (S-N), in meters
+/// * `zoom` - zoom level
+pub fn meters_to_tile(mx: f64, my: f64, zoom: u8) -> (u32, u32) {
+	let (lon, lat) = meters_to_lonlat(mx, my);
+	lonlat2tile(lon, lat, zoom)
+}
+
+/// Convert a Web Mercator tile to the Web Mercator (EPSG:3857) coordinates of its north-west corner.
+///
+/// # Arguments
+///
+/// * `x`    - X tile coordinate
+/// * `y`    - Y tile coordinate
+/// * `zoom` - zoom level
+pub fn tile_to_meters(x: u32, y: u32, zoom: u8) -> (f64, f64) {
+	tile_to_meters_frac(x as f64, y as f64, zoom)
+}
+
+/// Convert fractional tile coordinates to Web Mercator (EPSG:3857) coordinates.
+pub(crate) fn tile_to_meters_frac(x: f64, y: f64, zoom: u8) -> (f64, f64) {
+	let size = 2f64 * MAX_EXTENT / 2f64.powf(zoom as f64);
+	(x * size - MAX_EXTENT, MAX_EXTENT - y * size)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{tile2lonlat, tile2lonlat_frac, MAX_LATITUDE};
+
+	#[test]
+	fn test_meters() {
+		assert_eq!(lonlat_to_meters(0.0, 0.0), (0.0, 0.0));
+		assert_eq!(lonlat_to_meters(180.0, 0.0).0, MAX_EXTENT);
+		assert!((lonlat_to_meters(0.0, MAX_LATITUDE).1 - MAX_EXTENT).abs() < 1e-6);
+		assert_eq!(meters_to_lonlat(-MAX_EXTENT, 0.0), (-180.0, 0.0));
+
+		let (mx, my) = lonlat_to_meters(14.016667, 42.683333);
+		assert!((mx - 1560328.233).abs() < 1e-3 && (my - 5263895.440).abs() < 1e-3);
+		let (lon, lat) = meters_to_lonlat(mx, my);
+		assert!((lon - 14.016667).abs() < 1e-9 && (lat - 42.683333).abs() < 1e-9);
+	}
+
+	#[test]
+	fn test_meters_tile() {
+		let (mx, my) = lonlat_to_meters(14.016667, 42.683333);
+		assert_eq!(meters_to_tile(mx, my, 13), lonlat2tile(14.016667, 42.683333, 13));
+		assert_eq!(meters_to_tile(0.0, 0.0, 1), (1, 1));
+
+		assert_eq!(tile_to_meters(0, 0, 0), (-MAX_EXTENT, MAX_EXTENT));
+		assert_eq!(tile_to_meters(1, 1, 1), (0.0, 0.0));
+		let (lon, lat) = meters_to_lonlat(tile_to_meters(4376, 2932, 13).0, tile_to_meters(4376, 2932, 13).1);
+		let expected = tile2lonlat(4376, 2932, 13);
+		assert!((lon - expected.0).abs() < 1e-9 && (lat - expected.1).abs() < 1e-9);
+	}
+
+	#[test]
+	fn test_boundaries() {
+		// Tile corners convert back to the same column, and tile centers to the same tile.
+		for zoom in 1..=24 {
+			let n = 1u32 << zoom;
+			for i in 0..=64 {
+				let (x, y) = (i * (n - 1) / 64, (i * 7919) % n);
+				let (lon, lat) = tile2lonlat(x, y, zoom);
+				assert_eq!(lonlat2tile(lon, lat, zoom).0, x);
+				let (lon, lat) = tile2lonlat_frac(x as f64 + 0.5, y as f64 + 0.5, zoom);
+				assert_eq!(lonlat2tile(lon, lat, zoom), (x, y));
+				let (mx, my) = lonlat_to_meters(lon, lat);
+				assert_eq!(meters_to_tile(mx, my, zoom), (x, y));
+			}
+		}
+		assert_eq!(lonlat2tile(135.0, 0.0, 3), (7, 4));
+		assert_eq!(meters_to_tile(MAX_EXTENT * 0.75, 0.0, 3), (7, 4));
+	}
+}
@@ -0,0 +1,98 @@
+use crate::{lonlat2tile_frac, tile2lonlat_frac, Tile};
+
+/// Default extent of vector tiles, the number of integer units along each side of a tile.
+pub const DEFAULT_EXTENT: u32 = 4096;
+
+/// Convert lon/lat coordinates to integer coordinates local to a tile, as used by Mapbox Vector Tiles.
+///
+/// Local coordinates start at `(0, 0)` in the north-west corner of the tile, with the Y axis
+/// pointing down, and reach `(extent, extent)` in its south-east corner. Coordinates outside of
+/// the tile, such as those of a buffer around it, are negative or greater than the extent.
+///
+/// The coordinates are rounded on the grid of the whole map, so a point gets the same position
+/// in every tile, and points on a tile boundary are exactly on the edge of both adjacent tiles.
+///
+/// # Arguments
+///
+/// * `lon`    - longitude coordinate (W-E), in degrees
+/// * `lat`    - latitude  coordinate (N-S), in degrees
+/// * `x`      - X tile coordinate
+/// * `y`      - Y tile coordinate
+/// * `zoom`   - zoom level
+/// * `extent` - number of units along each side of the tile, usually [`DEFAULT_EXTENT`]
+pub fn lonlat2local(lon: f64, lat: f64, x: u32, y: u32, zoom: u8, extent: u32) -> (i32, i32) {
+	let (tx, ty) = lonlat2tile_frac(lon, lat, zoom);
+	let extent = extent as f64;
+	let lx = (tx * extent).round() - x as f64 * extent;
+	let ly = (ty * extent).round() - y as f64 * extent;
+	(lx as i32, ly as i32)
+}
+
+/// Convert integer coordinates local to a tile to lon/lat coordinates.
+///
+/// This is the inverse of [`lonlat2local`], up to its rounding. At `(0, 0)` the result
+/// is the same as [`crate::tile2lonlat`].
+///
+/// # Arguments
+///
+/// * `lx`     - X local coordinate
+/// * `ly`     - Y local coordinate
+/// * `x`      - X tile coordinate
+/// * `y`      - Y tile coordinate
+/// * `zoom`   - zoom level
+/// * `extent` - number of units along each side of the tile, usually [`DEFAULT_EXTENT`]
+pub fn local2lonlat(lx: i32, ly: i32, x: u32, y: u32, zoom: u8, extent: u32) -> (f64, f64) {
+	let extent = extent as f64;
+	tile2lonlat_frac((x as f64 * extent + lx as f64) / extent, (y as f64 * extent + ly as f64) / extent, zoom)
+}
+
+impl Tile {
+	/// Convert lon/lat coordinates to integer coordinates local to this tile, see [`lonlat2local`].
+	pub fn lonlat_to_local(&self, lon: f64, lat: f64, extent: u32) -> (i32, i32) {
+		lonlat2local(lon, lat, self.x(), self.y(), self.z(), extent)
+	}
+
+	/// Convert integer coordinates local to this tile to lon/lat coordinates, see [`local2lonlat`].
+	pub fn local_to_lonlat(&self, lx: i32, ly: i32, extent: u32) -> (f64, f64) {
+		local2lonlat(lx, ly, self.x(), self.y(), self.z(), extent)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tile2lonlat;
+
+	#[test]
+	fn test_local() {
+		let (lon, lat) = tile2lonlat(4376, 2932, 13);
+		assert_eq!(lonlat2local(lon, lat, 4376, 2932, 13, DEFAULT_EXTENT), (0, 0));
+		assert_eq!(local2lonlat(0, 0, 4376, 2932, 13, DEFAULT_EXTENT), (lon, lat));
+		assert_eq!(lonlat2local(0.0, 0.0, 0, 0, 0, DEFAULT_EXTENT), (2048, 2048));
+		assert_eq!(lonlat2local(180.0, -85.0511287798066, 0, 0, 0, 512), (512, 512));
+		assert_eq!(lonlat2local(0.0, 0.0, 1, 1, 1, DEFAULT_EXTENT), (0, 0));
+		assert_eq!(lonlat2local(0.0, 0.0, 0, 0, 1, DEFAULT_EXTENT), (4096, 4096));
+
+		let tile = Tile::new(4376, 2932, 13).unwrap();
+		let (lx, ly) = tile.lonlat_to_local(12.31, 45.45, DEFAULT_EXTENT);
+		assert!((0..4096).contains(&lx) && (0..4096).contains(&ly));
+		let (lon, lat) = tile.local_to_lonlat(lx, ly, DEFAULT_EXTENT);
+		assert!((lon - 12.31).abs() < 1e-5 && (lat - 45.45).abs() < 1e-5);
+		assert_eq!(tile.local_to_lonlat(4096, 4096, DEFAULT_EXTENT), tile2lonlat(4377, 2933, 13));
+	}
+
+	#[test]
+	fn test_adjacent() {
+		// The same point gets the same position in every tile, shifted by the extent.
+		let west = Tile::new(4376, 2932, 13).unwrap();
+		let east = Tile::new(4377, 2932, 13).unwrap();
+		let (lon, lat) = tile2lonlat(4377, 2932, 13);
+		assert_eq!(west.lonlat_to_local(lon, lat, DEFAULT_EXTENT), (4096, 0));
+		assert_eq!(east.lonlat_to_local(lon, lat, DEFAULT_EXTENT), (0, 0));
+		for (lon, lat) in [(12.3491, 45.44), (12.35, 45.4501), (12.34863, 45.43)] {
+			let (wx, wy) = west.lonlat_to_local(lon, lat, DEFAULT_EXTENT);
+			let (ex, ey) = east.lonlat_to_local(lon, lat, DEFAULT_EXTENT);
+			assert_eq!((wx - 4096, wy), (ex, ey));
+		}
+	}
+}
@@ -0,0 +1,87 @@
+use crate::Tile;
+
+impl Tile {
+	/// Tiles sharing an edge with this tile, in the order north, west, east, south.
+	///
+	/// The X coordinate wraps around the antimeridian, while there are no neighbors
+	/// beyond the northern and southern edges of the map.
+	pub fn neighbors4(&self) -> Vec<Tile> {
+		self.ring(1, |dx, dy| (dx == 0) != (dy == 0))
+	}
+
+	/// Tiles sharing an edge or a corner with this tile, row by row from north to south.
+	///
+	/// The X coordinate wraps around the antimeridian, while there are no neighbors
+	/// beyond the northern and southern edges of the map.
+	pub fn neighbors8(&self) -> Vec<Tile> {
+		self.ring(1, |dx, dy| dx != 0 || dy != 0)
+	}
+
+	/// Tiles at most `k` tiles away from this tile in any direction, including the tile itself.
+	///
+	/// The result forms a square of up to `(2k + 1)^2` tiles, listed row by row from north to south.
+	/// The X coordinate wraps around the antimeridian, so each tile appears only once
+	/// even if the square is wider than the map, while rows beyond the northern and
+	/// southern edges of the map are left out.
+	///
+	/// # Arguments
+	///
+	/// * `k` - distance from this tile, in tiles
+	pub fn k_ring(&self, k: u32) -> Vec<Tile> {
+		self.ring(k, |_, _| true)
+	}
+
+	fn ring(&self, k: u32, filter: impl Fn(i64, i64) -> bool) -> Vec<Tile> {
+		let n = 1i64 << self.z();
+		let (x, y, k) = (self.x() as i64, self.y() as i64, k as i64);
+
+		// Offsets covering at most one full turn around the map, so that no tile is repeated.
+		let (min_dx, max_dx) = (-k.min(n / 2), k.min((n - 1) / 2));
+		let mut tiles = Vec::new();
+		for dy in (-k).max(-y)..=k.min(n - 1 - y) {
+			for dx in min_dx..=max_dx {
+				if filter(dx, dy) {
+					tiles.extend(Tile::new((x + dx).rem_euclid(n) as u32, (y + dy) as u32, self.z()).ok());
+				}
+			}
+		}
+		tiles
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_neighbors() {
+		let t = Tile::new(5, 5, 4).unwrap();
+		assert_eq!(t.neighbors4(), [(5, 4), (4, 5), (6, 5), (5, 6)].map(|(x, y)| Tile::new(x, y, 4).unwrap()));
+		let expected = [(4, 4), (5, 4), (6, 4), (4, 5), (6, 5), (4, 6), (5, 6), (6, 6)];
+		assert_eq!(t.neighbors8(), expected.map(|(x, y)| Tile::new(x, y, 4).unwrap()));
+	}
+
+	#[test]
+	fn test_neighbors_edges() {
+		let t = Tile::new(0, 0, 2).unwrap();
+		assert_eq!(t.neighbors4(), [(3, 0), (1, 0), (0, 1)].map(|(x, y)| Tile::new(x, y, 2).unwrap()));
+		assert_eq!(t.neighbors8(), [(3, 0), (1, 0), (3, 1), (0, 1), (1, 1)].map(|(x, y)| Tile::new(x, y, 2).unwrap()));
+
+		let t = Tile::new(3, 3, 2).unwrap();
+		assert_eq!(t.neighbors8(), [(2, 2), (3, 2), (0, 2), (2, 3), (0, 3)].map(|(x, y)| Tile::new(x, y, 2).unwrap()));
+
+		let t = Tile::new(0, 0, 1).unwrap();
+		assert_eq!(t.neighbors8(), [(1, 0), (1, 1), (0, 1)].map(|(x, y)| Tile::new(x, y, 1).unwrap()));
+		assert_eq!(Tile::new(0, 0, 0).unwrap().neighbors8(), []);
+	}
+
+	#[test]
+	fn test_k_ring() {
+		let t = Tile::new(5, 5, 4).unwrap();
+		assert_eq!(t.k_ring(0), [t]);
+		assert_eq!(t.k_ring(2).len(), 25);
+		assert_eq!(Tile::new(5, 0, 4).unwrap().k_ring(2).len(), 15);
+		assert_eq!(Tile::new(1, 1, 2).unwrap().k_ring(10).len(), 16);
+		assert_eq!(Tile::new(0, 0, 0).unwrap().k_ring(3), [Tile::new(0, 0, 0).unwrap()]);
+	}
+}
@@ -0,0 +1,92 @@
+use crate::{lonlat2tile_frac, tile2lonlat_frac};
+
+/// Default tile size, in pixels.
+///
+/// Raster tiles are usually 256 pixels wide, or 512 pixels for high resolution tiles.
+pub const TILE_SIZE: u32 = 256;
+
+/// Convert lon/lat coordinates to global pixel coordinates at a given zoom level.
+///
+/// Global pixel coordinates start at the north-west corner of the map,
+/// which is `2^zoom * tile_size` pixels wide.
+///
+/// # Arguments
+///
+/// * `lon`       - longitude coordinate (W-E), in degrees
+/// * `lat`       - latitude  coordinate (N-S), in degrees
+/// * `zoom`      - zoom level
+/// * `tile_size` - tile size, in pixels
+pub fn lonlat2pixel(lon: f64, lat: f64, zoom: u8, tile_size: u32) -> (f64, f64) {
+	let (x, y) = lonlat2tile_frac(lon, lat, zoom);
+	(x * tile_size as f64, y * tile_size as f64)
+}
+
+/// Convert global pixel coordinates to lon/lat coordinates at a given zoom level.
+///
+/// At the corner of a tile, the result is the same as [`crate::tile2lonlat`].
+///
+/// # Arguments
+///
+/// * `px`        - X global pixel coordinate
+/// * `py`        - Y global pixel coordinate
+/// * `zoom`      - zoom level
+/// * `tile_size` - tile size, in pixels
+pub fn pixel2lonlat(px: f64, py: f64, zoom: u8, tile_size: u32) -> (f64, f64) {
+	tile2lonlat_frac(px / tile_size as f64, py / tile_size as f64, zoom)
+}
+
+/// Split global pixel coordinates into a tile and a pixel offset inside of that tile.
+///
+/// # Arguments
+///
+/// * `px`        - X global pixel coordinate
+/// * `py`        - Y global pixel coordinate
+/// * `tile_size` - tile size, in pixels
+pub fn pixel2tile(px: f64, py: f64, tile_size: u32) -> ((u32, u32), (f64, f64)) {
+	let size = tile_size as f64;
+	let (x, y) = ((px / size).floor(), (py / size).floor());
+	((x as u32, y as u32), (px - x * size, py - y * size))
+}
+
+/// Combine a tile and a pixel offset inside of that tile into global pixel coordinates.
+///
+/// # Arguments
+///
+/// * `x`         - X tile coordinate
+/// * `y`         - Y tile coordinate
+/// * `offset`    - pixel offset from the north-west corner of the tile
+/// * `tile_size` - tile size, in pixels
+pub fn tile2pixel(x: u32, y: u32, offset: (f64, f64), tile_size: u32) -> (f64, f64) {
+	let size = tile_size as f64;
+	(x as f64 * size + offset.0, y as f64 * size + offset.1)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{lonlat2tile, tile2lonlat};
+
+	#[test]
+	fn test_lonlat_pixel() {
+		assert_eq!(lonlat2pixel(0.0, 0.0, 0, TILE_SIZE), (128.0, 128.0));
+		assert_eq!(lonlat2pixel(-180.0, 0.0, 1, 512), (0.0, 512.0));
+		assert_eq!(pixel2lonlat(4376.0 * 256.0, 2932.0 * 256.0, 13, TILE_SIZE), tile2lonlat(4376, 2932, 13));
+		assert_eq!(pixel2lonlat(4376.0 * 512.0, 2932.0 * 512.0, 13, 512), tile2lonlat(4376, 2932, 13));
+
+		let (px, py) = lonlat2pixel(14.016667, 42.683333, 13, TILE_SIZE);
+		let (lon, lat) = pixel2lonlat(px, py, 13, TILE_SIZE);
+		assert!((lon - 14.016667).abs() < 1e-9 && (lat - 42.683333).abs() < 1e-9);
+	}
+
+	#[test]
+	fn test_tile_pixel() {
+		let (px, py) = lonlat2pixel(14.016667, 42.683333, 13, TILE_SIZE);
+		let (tile, (ox, oy)) = pixel2tile(px, py, TILE_SIZE);
+		assert_eq!(tile, lonlat2tile(14.016667, 42.683333, 13));
+		assert!((0.0..256.0).contains(&ox) && (0.0..256.0).contains(&oy));
+		assert_eq!(tile2pixel(tile.0, tile.1, (ox, oy), TILE_SIZE), (px, py));
+
+		assert_eq!(pixel2tile(512.0, 300.5, 256), ((2, 1), (0.0, 44.5)));
+		assert_eq!(tile2pixel(2, 1, (0.0, 44.5), 256), (512.0, 300.5));
+	}
+}
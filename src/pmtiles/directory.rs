@@ -0,0 +1,133 @@
+use crate::pmtiles::Error;
+
+/// Entry of a PMTiles directory.
+///
+/// An entry with a run length of 0 points to a leaf directory, otherwise it points to
+/// the tile data shared by `run_length` consecutive tile IDs starting at `tile_id`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Entry {
+	pub tile_id: u64,
+	pub offset: u64,
+	pub length: u32,
+	pub run_length: u32,
+}
+
+/// Read an unsigned LEB128 varint.
+fn read_varint(bytes: &mut &[u8]) -> Result<u64, Error> {
+	let mut value = 0u64;
+	for shift in (0..64).step_by(7) {
+		let (&byte, rest) = bytes.split_first().ok_or(Error::InvalidDirectory)?;
+		*bytes = rest;
+		value |= ((byte & 0x7f) as u64) << shift;
+		if byte & 0x80 == 0 {
+			return Ok(value);
+		}
+	}
+	Err(Error::InvalidDirectory)
+}
+
+/// Write an unsigned LEB128 varint.
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+	while value >= 0x80 {
+		out.push((value as u8 & 0x7f) | 0x80);
+		value >>= 7;
+	}
+	out.push(value as u8);
+}
+
+/// Encode a directory, without compression.
+pub(crate) fn encode(entries: &[Entry]) -> Vec<u8> {
+	let mut out = Vec::new();
+	write_varint(&mut out, entries.len() as u64);
+	let mut tile_id = 0;
+	for entry in entries {
+		write_varint(&mut out, entry.tile_id - tile_id);
+		tile_id = entry.tile_id;
+	}
+	for entry in entries {
+		write_varint(&mut out, entry.run_length as u64);
+	}
+	for entry in entries {
+		write_varint(&mut out, entry.length as u64);
+	}
+	for (i, entry) in entries.iter().enumerate() {
+		let contiguous = i > 0 && entry.offset == entries[i - 1].offset + entries[i - 1].length as u64;
+		write_varint(&mut out, if contiguous { 0 } else { entry.offset + 1 });
+	}
+	out
+}
+
+/// Decode an uncompressed directory.
+pub(crate) fn decode(mut bytes: &[u8]) -> Result<Vec<Entry>, Error> {
+	let bytes = &mut bytes;
+	let count = read_varint(bytes)? as usize;
+	// Each entry takes at least 4 bytes, which bounds the allocation for corrupted input.
+	if count > bytes.len() / 4 {
+		return Err(Error::InvalidDirectory);
+	}
+
+	let mut entries = vec![Entry { tile_id: 0, offset: 0, length: 0, run_length: 0 }; count];
+	let mut tile_id = 0u64;
+	for entry in entries.iter_mut() {
+		tile_id = tile_id.checked_add(read_varint(bytes)?).ok_or(Error::InvalidDirectory)?;
+		entry.tile_id = tile_id;
+	}
+	for entry in entries.iter_mut() {
+		entry.run_length = read_varint(bytes)?.try_into().map_err(|_| Error::InvalidDirectory)?;
+	}
+	for entry in entries.iter_mut() {
+		entry.length = read_varint(bytes)?.try_into().map_err(|_| Error::InvalidDirectory)?;
+	}
+	for i in 0..count {
+		let offset = read_varint(bytes)?;
+		entries[i].offset = match (offset, i) {
+			(0, 1..) => {
+				let previous = &entries[i - 1];
+				previous.offset.checked_add(previous.length as u64).ok_or(Error::InvalidDirectory)?
+			}
+			(0, _) => return Err(Error::InvalidDirectory),
+			_ => offset - 1,
+		};
+	}
+	Ok(entries)
+}
+
+/// Find the entry covering a tile ID, or the leaf directory that may contain it.
+pub(crate) fn find(entries: &[Entry], tile_id: u64) -> Option<&Entry> {
+	let i = entries.partition_point(|entry| entry.tile_id <= tile_id).checked_sub(1)?;
+	let entry = &entries[i];
+	if entry.run_length == 0 || tile_id - entry.tile_id < entry.run_length as u64 {
+		Some(entry)
+	} else {
+		None
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_decode() {
+		// 3 entries: IDs 0, 1, 5; run lengths 1, 2, 0; lengths 10, 300, 20; offsets 0, implicit, 1000.
+		let bytes = [3, 0, 1, 4, 1, 2, 0, 10, 0xac, 0x02, 20, 1, 0, 0xe9, 0x07];
+		let entries = decode(&bytes).unwrap();
+		assert_eq!(entries.len(), 3);
+		assert_eq!(entries[1], Entry { tile_id: 1, offset: 10, length: 300, run_length: 2 });
+		assert_eq!(entries[2], Entry { tile_id: 5, offset: 1000, length: 20, run_length: 0 });
+
+		assert_eq!(find(&entries, 0), Some(&entries[0]));
+		assert_eq!(find(&entries, 2), Some(&entries[1]));
+		assert_eq!(find(&entries, 3), None);
+		assert_eq!(find(&entries, 7), Some(&entries[2]));
+
+		assert_eq!(encode(&entries), bytes);
+
+		assert!(matches!(decode(&bytes[..10]), Err(Error::InvalidDirectory)));
+		assert!(matches!(decode(&[0xff; 12]), Err(Error::InvalidDirectory)));
+
+		// 2 entries whose first offset is u64::MAX, and whose implicit second offset overflows.
+		let corrupt = [2, 0, 1, 1, 1, 2, 1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01, 0];
+		assert!(matches!(decode(&corrupt), Err(Error::InvalidDirectory)));
+	}
+}
@@ -0,0 +1,177 @@
+use crate::pmtiles::{Compression, Error, TileType};
+use crate::Bounds;
+
+/// Length of the PMTiles v3 header, in bytes.
+pub const HEADER_LEN: usize = 127;
+
+const MAGIC: &[u8; 7] = b"PMTiles";
+const VERSION: u8 = 3;
+
+/// Header of a PMTiles v3 archive.
+///
+/// Offsets are counted from the start of the archive, and lengths are in bytes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Header {
+	/// Offset of the root directory.
+	pub root_offset: u64,
+	/// Length of the root directory.
+	pub root_length: u64,
+	/// Offset of the JSON metadata.
+	pub metadata_offset: u64,
+	/// Length of the JSON metadata.
+	pub metadata_length: u64,
+	/// Offset of the section holding the leaf directories.
+	pub leaf_offset: u64,
+	/// Length of the section holding the leaf directories.
+	pub leaf_length: u64,
+	/// Offset of the section holding the tile data.
+	pub data_offset: u64,
+	/// Length of the section holding the tile data.
+	pub data_length: u64,
+	/// Number of tiles present in the archive, or 0 if unknown.
+	pub addressed_tiles: u64,
+	/// Number of directory entries pointing to tile data, or 0 if unknown.
+	pub tile_entries: u64,
+	/// Number of distinct tile contents, or 0 if unknown.
+	pub tile_contents: u64,
+	/// Whether the tile data is ordered by tile ID.
+	pub clustered: bool,
+	/// Compression of the directories and the metadata.
+	pub internal_compression: Compression,
+	/// Compression of the tile data.
+	pub tile_compression: Compression,
+	/// Format of the tile data.
+	pub tile_type: TileType,
+	/// Lowest zoom level of the tiles.
+	pub min_zoom: u8,
+	/// Highest zoom level of the tiles.
+	pub max_zoom: u8,
+	/// Lon/lat bounds of the tiles, in degrees.
+	pub bounds: Bounds,
+	/// Zoom level at which the archive should initially be displayed.
+	pub center_zoom: u8,
+	/// Lon/lat coordinates at which the archive should initially be displayed, in degrees.
+	pub center: (f64, f64),
+}
+
+impl Header {
+	/// Parse a header from the first bytes of an archive.
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+		if bytes.len() < HEADER_LEN || &bytes[..7] != MAGIC {
+			return Err(Error::InvalidMagic);
+		}
+		if bytes[7] != VERSION {
+			return Err(Error::UnsupportedVersion(bytes[7]));
+		}
+
+		let u64_at = |i: usize| u64::from_le_bytes(bytes[i..i + 8].try_into().unwrap());
+		let e7_at = |i: usize| i32::from_le_bytes(bytes[i..i + 4].try_into().unwrap()) as f64 / 1e7;
+		Ok(Self {
+			root_offset: u64_at(8),
+			root_length: u64_at(16),
+			metadata_offset: u64_at(24),
+			metadata_length: u64_at(32),
+			leaf_offset: u64_at(40),
+			leaf_length: u64_at(48),
+			data_offset: u64_at(56),
+			data_length: u64_at(64),
+			addressed_tiles: u64_at(72),
+			tile_entries: u64_at(80),
+			tile_contents: u64_at(88),
+			clustered: bytes[96] == 1,
+			internal_compression: Compression::from(bytes[97]),
+			tile_compression: Compression::from(bytes[98]),
+			tile_type: TileType::from(bytes[99]),
+			min_zoom: bytes[100],
+			max_zoom: bytes[101],
+			bounds: Bounds::new(e7_at(102), e7_at(106), e7_at(110), e7_at(114)),
+			center_zoom: bytes[118],
+			center: (e7_at(119), e7_at(123)),
+		})
+	}
+
+	/// Serialize the header to the first bytes of an archive.
+	pub fn to_bytes(&self) -> [u8; HEADER_LEN] {
+		let mut bytes = [0u8; HEADER_LEN];
+		bytes[..7].copy_from_slice(MAGIC);
+		bytes[7] = VERSION;
+
+		let fields = [
+			self.root_offset,
+			self.root_length,
+			self.metadata_offset,
+			self.metadata_length,
+			self.leaf_offset,
+			self.leaf_length,
+			self.data_offset,
+			self.data_length,
+			self.addressed_tiles,
+			self.tile_entries,
+			self.tile_contents,
+		];
+		for (i, field) in fields.iter().enumerate() {
+			bytes[8 + 8 * i..16 + 8 * i].copy_from_slice(&field.to_le_bytes());
+		}
+
+		bytes[96] = self.clustered as u8;
+		bytes[97] = self.internal_compression.into();
+		bytes[98] = self.tile_compression.into();
+		bytes[99] = self.tile_type.into();
+		bytes[100] = self.min_zoom;
+		bytes[101] = self.max_zoom;
+		bytes[118] = self.center_zoom;
+
+		let coords = [
+			(102, self.bounds.west),
+			(106, self.bounds.south),
+			(110, self.bounds.east),
+			(114, self.bounds.north),
+			(119, self.center.0),
+			(123, self.center.1),
+		];
+		for (i, coord) in coords {
+			bytes[i..i + 4].copy_from_slice(&((coord * 1e7).round() as i32).to_le_bytes());
+		}
+		bytes
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_header() {
+		let header = Header {
+			root_offset: 127,
+			root_length: 25,
+			metadata_offset: 152,
+			metadata_length: 2,
+			leaf_offset: 154,
+			leaf_length: 0,
+			data_offset: 154,
+			data_length: 1000,
+			addressed_tiles: 10,
+			tile_entries: 8,
+			tile_contents: 5,
+			clustered: true,
+			internal_compression: Compression::Gzip,
+			tile_compression: Compression::None,
+			tile_type: TileType::Png,
+			min_zoom: 0,
+			max_zoom: 14,
+			bounds: Bounds::new(-180.0, -85.0511287, 180.0, 85.0511287),
+			center_zoom: 3,
+			center: (12.3046875, 45.4601306),
+		};
+		let bytes = header.to_bytes();
+		assert_eq!(&bytes[..8], b"PMTiles\x03");
+
+		assert_eq!(Header::from_bytes(&bytes).unwrap(), header);
+
+		assert!(matches!(Header::from_bytes(&bytes[..100]), Err(Error::InvalidMagic)));
+		let mut v2 = bytes;
+		v2[7] = 2;
+		assert!(matches!(Header::from_bytes(&v2), Err(Error::UnsupportedVersion(2))));
+	}
+}
@@ -0,0 +1,317 @@
+//! Support for [PMTiles v3](https://github.com/protomaps/PMTiles/blob/main/spec/v3/spec.md) archives.
+//!
+//! Archives whose directories are compressed with gzip require the `gzip` feature.
+
+use std::error;
+use std::fmt;
+use std::io;
+
+use crate::{Tile, TileError, MAX_ZOOM};
+
+mod directory;
+mod header;
+mod reader;
+mod writer;
+
+pub use header::{Header, HEADER_LEN};
+pub use reader::{RangeReader, Reader};
+pub use writer::Writer;
+
+/// Compression of the directories, metadata or tiles of an archive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Compression {
+	/// Compression not specified, or not known to this crate (value 0 or unassigned values).
+	Unknown,
+	/// No compression (value 1).
+	None,
+	/// Gzip compression (value 2).
+	Gzip,
+	/// Brotli compression (value 3).
+	Brotli,
+	/// Zstandard compression (value 4).
+	Zstd,
+}
+
+impl From<u8> for Compression {
+	fn from(value: u8) -> Self {
+		match value {
+			1 => Compression::None,
+			2 => Compression::Gzip,
+			3 => Compression::Brotli,
+			4 => Compression::Zstd,
+			_ => Compression::Unknown,
+		}
+	}
+}
+
+impl From<Compression> for u8 {
+	fn from(value: Compression) -> Self {
+		match value {
+			Compression::Unknown => 0,
+			Compression::None => 1,
+			Compression::Gzip => 2,
+			Compression::Brotli => 3,
+			Compression::Zstd => 4,
+		}
+	}
+}
+
+/// Format of the tiles of an archive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TileType {
+	/// Format not specified, or not known to this crate (value 0 or unassigned values).
+	Unknown,
+	/// Mapbox Vector Tiles (value 1).
+	Mvt,
+	/// PNG images (value 2).
+	Png,
+	/// JPEG images (value 3).
+	Jpeg,
+	/// WebP images (value 4).
+	Webp,
+	/// AVIF images (value 5).
+	Avif,
+}
+
+impl From<u8> for TileType {
+	fn from(value: u8) -> Self {
+		match value {
+			1 => TileType::Mvt,
+			2 => TileType::Png,
+			3 => TileType::Jpeg,
+			4 => TileType::Webp,
+			5 => TileType::Avif,
+			_ => TileType::Unknown,
+		}
+	}
+}
+
+impl From<TileType> for u8 {
+	fn from(value: TileType) -> Self {
+		match value {
+			TileType::Unknown => 0,
+			TileType::Mvt => 1,
+			TileType::Png => 2,
+			TileType::Jpeg => 3,
+			TileType::Webp => 4,
+			TileType::Avif => 5,
+		}
+	}
+}
+
+/// Error returned when reading or writing an archive, or converting a tile ID, fails.
+#[derive(Debug)]
+pub enum Error {
+	/// Reading from or writing to the underlying storage failed.
+	Io(io::Error),
+	/// The data does not start with a PMTiles header.
+	InvalidMagic,
+	/// The archive is not a version 3 archive.
+	UnsupportedVersion(u8),
+	/// The directories or metadata use a compression that is not supported.
+	UnsupportedCompression(Compression),
+	/// A directory is malformed.
+	InvalidDirectory,
+	/// A tile or leaf directory is too large to be addressed by a directory entry (4 GiB).
+	TooLarge,
+	/// The tile ID is beyond the last tile at [`MAX_ZOOM`].
+	InvalidTileId(u64),
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Error::Io(err) => write!(f, "I/O error: {err}"),
+			Error::InvalidMagic => write!(f, "not a PMTiles archive"),
+			Error::UnsupportedVersion(version) => write!(f, "unsupported PMTiles version {version}"),
+			Error::UnsupportedCompression(compression) => write!(f, "unsupported compression {compression:?}"),
+			Error::InvalidDirectory => write!(f, "malformed directory"),
+			Error::TooLarge => write!(f, "tile or directory larger than 4 GiB"),
+			Error::InvalidTileId(id) => write!(f, "tile ID {id} is out of range"),
+		}
+	}
+}
+
+impl error::Error for Error {
+	fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+		match self {
+			Error::Io(err) => Some(err),
+			_ => None,
+		}
+	}
+}
+
+impl From<io::Error> for Error {
+	fn from(err: io::Error) -> Self {
+		Error::Io(err)
+	}
+}
+
+/// Compress directories or metadata.
+pub(crate) fn compress(data: Vec<u8>, compression: Compression) -> Result<Vec<u8>, Error> {
+	match compression {
+		Compression::None => Ok(data),
+		#[cfg(feature = "gzip")]
+		Compression::Gzip => {
+			use std::io::Write;
+
+			let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+			encoder.write_all(&data)?;
+			Ok(encoder.finish()?)
+		}
+		_ => Err(Error::UnsupportedCompression(compression)),
+	}
+}
+
+/// Decompress directories or metadata.
+pub(crate) fn decompress(data: Vec<u8>, compression: Compression) -> Result<Vec<u8>, Error> {
+	match compression {
+		Compression::None => Ok(data),
+		#[cfg(feature = "gzip")]
+		Compression::Gzip => {
+			use std::io::Read;
+
+			let mut out = Vec::new();
+			flate2::read::GzDecoder::new(&data[..]).read_to_end(&mut out)?;
+			Ok(out)
+		}
+		_ => Err(Error::UnsupportedCompression(compression)),
+	}
+}
+
+/// Convert a Web Mercator tile to its PMTiles v3 tile ID.
+///
+/// Tile IDs number the tiles of all zoom levels in a single sequence: the tiles of lower
+/// zoom levels come first, and the tiles of a zoom level are ordered along a Hilbert curve.
+/// Unlike most free functions of this crate, the input is checked, as with [`Tile::new`].
+///
+/// # Arguments
+///
+/// * `x`    - X tile coordinate
+/// * `y`    - Y tile coordinate
+/// * `zoom` - zoom level
+pub fn tile2pmtiles_id(x: u32, y: u32, zoom: u8) -> Result<u64, TileError> {
+	Ok(Tile::new(x, y, zoom)?.to_pmtiles_id())
+}
+
+/// Hilbert tile ID of tile coordinates that are valid for their zoom level.
+fn tile_id(x: u32, y: u32, zoom: u8) -> u64 {
+	let base = ((1u64 << (2 * zoom as u32)) - 1) / 3;
+	let n = 1u64 << zoom;
+	let (mut x, mut y) = (x as u64, y as u64);
+	let mut d = 0;
+	let mut s = n / 2;
+	while s > 0 {
+		let rx = (x & s != 0) as u64;
+		let ry = (y & s != 0) as u64;
+		d += s * s * ((3 * rx) ^ ry);
+		(x, y) = rotate(n, x, y, rx, ry);
+		s /= 2;
+	}
+	base + d
+}
+
+/// Convert a PMTiles v3 tile ID to a Web Mercator tile.
+///
+/// This is the inverse of [`tile2pmtiles_id`].
+///
+/// # Arguments
+///
+/// * `id` - tile ID
+pub fn pmtiles_id2tile(id: u64) -> Result<Tile, Error> {
+	let mut base = 0u64;
+	for zoom in 0..=MAX_ZOOM {
+		let count = 1u64 << (2 * zoom as u32);
+		if id - base < count {
+			let mut t = id - base;
+			let (mut x, mut y) = (0, 0);
+			let mut s = 1;
+			while s < 1u64 << zoom {
+				let rx = 1 & (t / 2);
+				let ry = 1 & (t ^ rx);
+				(x, y) = rotate(s, x, y, rx, ry);
+				x += s * rx;
+				y += s * ry;
+				t /= 4;
+				s *= 2;
+			}
+			return Tile::new(x as u32, y as u32, zoom).map_err(|_| Error::InvalidTileId(id));
+		}
+		base += count;
+	}
+	Err(Error::InvalidTileId(id))
+}
+
+/// Rotate a quadrant of the Hilbert curve.
+fn rotate(n: u64, x: u64, y: u64, rx: u64, ry: u64) -> (u64, u64) {
+	if ry != 0 {
+		return (x, y);
+	}
+	if rx == 1 {
+		(n - 1 - y, n - 1 - x)
+	} else {
+		(y, x)
+	}
+}
+
+impl Tile {
+	/// PMTiles v3 tile ID of this tile, see [`tile2pmtiles_id`].
+	pub fn to_pmtiles_id(&self) -> u64 {
+		tile_id(self.x(), self.y(), self.z())
+	}
+
+	/// Create a tile from a PMTiles v3 tile ID, see [`pmtiles_id2tile`].
+	pub fn from_pmtiles_id(id: u64) -> Result<Self, Error> {
+		pmtiles_id2tile(id)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_tile_id() {
+		assert_eq!(tile2pmtiles_id(0, 0, 0), Ok(0));
+		assert_eq!(tile2pmtiles_id(0, 0, 1), Ok(1));
+		assert_eq!(tile2pmtiles_id(0, 1, 1), Ok(2));
+		assert_eq!(tile2pmtiles_id(1, 1, 1), Ok(3));
+		assert_eq!(tile2pmtiles_id(1, 0, 1), Ok(4));
+		assert_eq!(tile2pmtiles_id(0, 0, 2), Ok(5));
+		assert_eq!(tile2pmtiles_id(3423, 1763, 12), Ok(19078479));
+		assert_eq!(tile2pmtiles_id(0, 0, 32), Err(TileError::InvalidZoom(32)));
+		assert_eq!(tile2pmtiles_id(1, 2, 1), Err(TileError::OutOfRange { x: 1, y: 2, z: 1 }));
+	}
+
+	#[test]
+	fn test_tile_from_id() {
+		for id in 0..1365 {
+			assert_eq!(pmtiles_id2tile(id).unwrap().to_pmtiles_id(), id);
+		}
+		assert_eq!(pmtiles_id2tile(19078479).unwrap(), Tile::new(3423, 1763, 12).unwrap());
+
+		let tile = Tile::new(u32::MAX >> 1, 0, MAX_ZOOM).unwrap();
+		assert_eq!(Tile::from_pmtiles_id(tile.to_pmtiles_id()).unwrap(), tile);
+		let last = Tile::new(u32::MAX >> 1, u32::MAX >> 1, MAX_ZOOM).unwrap().to_pmtiles_id();
+		let end = u64::MAX / 3;
+		assert!(last < end);
+		assert!(matches!(pmtiles_id2tile(end), Err(Error::InvalidTileId(id)) if id == end));
+	}
+
+	#[cfg(feature = "gzip")]
+	#[test]
+	fn test_compress() {
+		let compressed = compress(b"{}".to_vec(), Compression::Gzip).unwrap();
+		assert_eq!(decompress(compressed, Compression::Gzip).unwrap(), b"{}");
+		assert!(matches!(decompress(b"{}".to_vec(), Compression::Gzip), Err(Error::Io(_))));
+	}
+
+	#[test]
+	fn test_tile_id_zoom() {
+		// Children of a tile are numbered after all the tiles of its zoom level.
+		let tile = Tile::new(4376, 2932, 13).unwrap();
+		let (a, b, c, d) = tile.zoom_in().unwrap();
+		assert!([a, b, c, d].iter().all(|child| child.to_pmtiles_id() > tile.to_pmtiles_id()));
+		assert_eq!(Tile::from_pmtiles_id(a.to_pmtiles_id()).unwrap().zoom_out(), Some(tile));
+	}
+}
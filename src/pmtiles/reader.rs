@@ -0,0 +1,168 @@
+use std::io::{self, Read, Seek, SeekFrom};
+
+use crate::pmtiles::directory::{self, Entry};
+use crate::pmtiles::{decompress, Error, Header, HEADER_LEN};
+use crate::Tile;
+
+/// Maximum number of nested directories, as allowed by the specification.
+const MAX_DEPTH: usize = 3;
+
+/// Source of byte ranges, such as a file or an HTTP server supporting range requests.
+///
+/// This is implemented for every type implementing [`Read`] and [`Seek`].
+pub trait RangeReader {
+	/// Read `length` bytes starting at `offset`.
+	fn read_range(&mut self, offset: u64, length: u64) -> io::Result<Vec<u8>>;
+}
+
+impl<T: Read + Seek> RangeReader for T {
+	fn read_range(&mut self, offset: u64, length: u64) -> io::Result<Vec<u8>> {
+		self.seek(SeekFrom::Start(offset))?;
+		let mut buf = Vec::new();
+		self.take(length).read_to_end(&mut buf)?;
+		if buf.len() as u64 != length {
+			return Err(io::ErrorKind::UnexpectedEof.into());
+		}
+		Ok(buf)
+	}
+}
+
+/// Reader looking up tiles in a PMTiles v3 archive.
+///
+/// The header and the root directory are read once when the reader is created,
+/// leaf directories are read every time a tile needs them.
+#[derive(Debug)]
+pub struct Reader<R> {
+	source: R,
+	header: Header,
+	root: Vec<Entry>,
+}
+
+impl<R: RangeReader> Reader<R> {
+	/// Open an archive, reading its header and root directory.
+	pub fn new(mut source: R) -> Result<Self, Error> {
+		let header = Header::from_bytes(&source.read_range(0, HEADER_LEN as u64)?)?;
+		let root = source.read_range(header.root_offset, header.root_length)?;
+		let root = directory::decode(&decompress(root, header.internal_compression)?)?;
+		Ok(Self { source, header, root })
+	}
+
+	/// Header of the archive.
+	pub fn header(&self) -> &Header {
+		&self.header
+	}
+
+	/// JSON metadata of the archive.
+	pub fn metadata(&mut self) -> Result<String, Error> {
+		let metadata = self.source.read_range(self.header.metadata_offset, self.header.metadata_length)?;
+		let metadata = decompress(metadata, self.header.internal_compression)?;
+		String::from_utf8(metadata).map_err(|err| Error::Io(io::Error::new(io::ErrorKind::InvalidData, err)))
+	}
+
+	/// Data of a tile, or `None` if the archive does not contain the tile.
+	///
+	/// The data is returned as stored, compressed with [`Header::tile_compression`].
+	pub fn get_tile(&mut self, tile: Tile) -> Result<Option<Vec<u8>>, Error> {
+		let tile_id = tile.to_pmtiles_id();
+		let mut entry = directory::find(&self.root, tile_id).copied();
+		for _ in 0..MAX_DEPTH {
+			match entry {
+				None => return Ok(None),
+				Some(Entry { run_length: 0, offset, length, .. }) => {
+					let offset = self.header.leaf_offset.checked_add(offset).ok_or(Error::InvalidDirectory)?;
+					let leaf = self.source.read_range(offset, length as u64)?;
+					let leaf = directory::decode(&decompress(leaf, self.header.internal_compression)?)?;
+					entry = directory::find(&leaf, tile_id).copied();
+				}
+				Some(Entry { offset, length, .. }) => {
+					let offset = self.header.data_offset.checked_add(offset).ok_or(Error::InvalidDirectory)?;
+					return Ok(Some(self.source.read_range(offset, length as u64)?));
+				}
+			}
+		}
+		Err(Error::InvalidDirectory)
+	}
+
+	/// Give back the underlying source.
+	pub fn into_inner(self) -> R {
+		self.source
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::io::Cursor;
+
+	use super::*;
+	use crate::pmtiles::{Compression, TileType};
+	use crate::Bounds;
+
+	/// Archive with tile 0/0/0 in the root directory and the tiles of zoom 1 in a leaf directory.
+	fn archive() -> Vec<u8> {
+		// Root: ID 0 -> data 0..3, ID 1 -> leaf 0..9.
+		let root = [2, 0, 1, 1, 0, 3, 9, 1, 1];
+		// Leaf: IDs 1-2 -> data 3..6, ID 4 -> data 6..9.
+		let leaf = [2, 1, 3, 2, 1, 3, 3, 4, 7];
+		let metadata = br#"{"name":"test"}"#;
+		let data = b"abcdefghi";
+
+		let root_offset = HEADER_LEN as u64;
+		let metadata_offset = root_offset + root.len() as u64;
+		let leaf_offset = metadata_offset + metadata.len() as u64;
+		let data_offset = leaf_offset + leaf.len() as u64;
+		let header = Header {
+			root_offset,
+			root_length: root.len() as u64,
+			metadata_offset,
+			metadata_length: metadata.len() as u64,
+			leaf_offset,
+			leaf_length: leaf.len() as u64,
+			data_offset,
+			data_length: data.len() as u64,
+			addressed_tiles: 4,
+			tile_entries: 3,
+			tile_contents: 3,
+			clustered: true,
+			internal_compression: Compression::None,
+			tile_compression: Compression::None,
+			tile_type: TileType::Png,
+			min_zoom: 0,
+			max_zoom: 1,
+			bounds: Bounds::new(-180.0, -85.0, 180.0, 85.0),
+			center_zoom: 0,
+			center: (0.0, 0.0),
+		};
+		[&header.to_bytes()[..], &root, metadata, &leaf, data].concat()
+	}
+
+	fn get(reader: &mut Reader<Cursor<Vec<u8>>>, x: u32, y: u32, z: u8) -> Option<Vec<u8>> {
+		reader.get_tile(Tile::new(x, y, z).unwrap()).unwrap()
+	}
+
+	#[test]
+	fn test_reader() {
+		let mut reader = Reader::new(Cursor::new(archive())).unwrap();
+		assert_eq!(reader.header().max_zoom, 1);
+		assert_eq!(reader.metadata().unwrap(), r#"{"name":"test"}"#);
+
+		assert_eq!(get(&mut reader, 0, 0, 0).as_deref(), Some(&b"abc"[..]));
+		assert_eq!(get(&mut reader, 0, 0, 1).as_deref(), Some(&b"def"[..]));
+		assert_eq!(get(&mut reader, 0, 1, 1).as_deref(), Some(&b"def"[..]));
+		assert_eq!(get(&mut reader, 1, 1, 1), None);
+		assert_eq!(get(&mut reader, 1, 0, 1).as_deref(), Some(&b"ghi"[..]));
+		assert_eq!(get(&mut reader, 0, 0, 2), None);
+	}
+
+	#[test]
+	fn test_reader_errors() {
+		assert!(matches!(Reader::new(Cursor::new(vec![0; 200])), Err(Error::InvalidMagic)));
+
+		let mut truncated = archive();
+		truncated.truncate(HEADER_LEN + 4);
+		assert!(matches!(Reader::new(Cursor::new(truncated)), Err(Error::Io(_))));
+
+		let mut gzip = archive();
+		gzip[97] = Compression::Brotli.into();
+		assert!(matches!(Reader::new(Cursor::new(gzip)), Err(Error::UnsupportedCompression(Compression::Brotli))));
+	}
+}
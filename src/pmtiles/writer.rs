@@ -0,0 +1,299 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::io::Write;
+
+use crate::pmtiles::directory::{self, Entry};
+use crate::pmtiles::{compress, pmtiles_id2tile, Compression, Error, Header, TileType, HEADER_LEN};
+use crate::{tile2lonlat, Bounds, Tile};
+
+/// Maximum size of the header and root directory, so that clients can fetch both in one request.
+const ROOT_MAX_LEN: usize = 16384;
+
+/// Writer building a PMTiles v3 archive.
+///
+/// Tiles can be added in any order. They are kept in memory until [`Writer::finish`]
+/// writes the archive, with the tile data ordered by tile ID and identical tiles stored once.
+#[derive(Debug)]
+pub struct Writer {
+	tile_type: TileType,
+	tile_compression: Compression,
+	internal_compression: Compression,
+	metadata: String,
+	tiles: HashMap<u64, usize>,
+	contents: Vec<Vec<u8>>,
+	hashes: HashMap<u64, Vec<usize>>,
+}
+
+impl Writer {
+	/// Create a writer for tiles of the given type and compression.
+	///
+	/// The tile data is stored as given, `tile_compression` only describes it in the header.
+	/// Directories and metadata are compressed with gzip if the `gzip` feature is enabled.
+	pub fn new(tile_type: TileType, tile_compression: Compression) -> Self {
+		Self {
+			tile_type,
+			tile_compression,
+			internal_compression: if cfg!(feature = "gzip") { Compression::Gzip } else { Compression::None },
+			metadata: String::from("{}"),
+			tiles: HashMap::new(),
+			contents: Vec::new(),
+			hashes: HashMap::new(),
+		}
+	}
+
+	/// Set the compression of the directories and metadata.
+	///
+	/// Only [`Compression::None`] and, with the `gzip` feature, [`Compression::Gzip`] are supported.
+	pub fn set_internal_compression(&mut self, compression: Compression) {
+		self.internal_compression = compression;
+	}
+
+	/// Set the JSON metadata of the archive, `{}` by default.
+	pub fn set_metadata(&mut self, metadata: impl Into<String>) {
+		self.metadata = metadata.into();
+	}
+
+	/// Add a tile to the archive, replacing any previous data for the same tile.
+	pub fn add_tile(&mut self, tile: Tile, data: Vec<u8>) {
+		let mut hasher = DefaultHasher::new();
+		data.hash(&mut hasher);
+		let candidates = self.hashes.entry(hasher.finish()).or_default();
+
+		let index = match candidates.iter().find(|&&i| self.contents[i] == data) {
+			Some(&i) => i,
+			None => {
+				candidates.push(self.contents.len());
+				self.contents.push(data);
+				self.contents.len() - 1
+			}
+		};
+		self.tiles.insert(tile.to_pmtiles_id(), index);
+	}
+
+	/// Number of tiles added so far.
+	pub fn len(&self) -> usize {
+		self.tiles.len()
+	}
+
+	/// Whether no tile has been added so far.
+	pub fn is_empty(&self) -> bool {
+		self.tiles.is_empty()
+	}
+
+	/// Write the archive, returning its header.
+	pub fn finish<W: Write>(self, mut out: W) -> Result<Header, Error> {
+		let mut ids: Vec<_> = self.tiles.keys().copied().collect();
+		ids.sort_unstable();
+
+		// Lay out the tile data in tile ID order, merging runs of identical consecutive tiles.
+		let mut data = Vec::new();
+		let mut offsets: HashMap<usize, u64> = HashMap::new();
+		let mut entries: Vec<Entry> = Vec::new();
+		let mut last_content = None;
+		for &id in &ids {
+			let content = self.tiles[&id];
+			if let Some(last) = entries.last_mut() {
+				if last_content == Some(content) && last.tile_id + last.run_length as u64 == id {
+					last.run_length += 1;
+					continue;
+				}
+			}
+			let offset = *offsets.entry(content).or_insert_with(|| {
+				data.extend_from_slice(&self.contents[content]);
+				(data.len() - self.contents[content].len()) as u64
+			});
+			let length = self.contents[content].len().try_into().map_err(|_| Error::TooLarge)?;
+			entries.push(Entry { tile_id: id, offset, length, run_length: 1 });
+			last_content = Some(content);
+		}
+
+		let (root, leaves) = self.build_directories(&entries)?;
+		let metadata = compress(self.metadata.into_bytes(), self.internal_compression)?;
+
+		// Tile IDs grow with the zoom level, so the first and last IDs give the zoom range.
+		let zoom_of = |id: Option<&u64>| id.and_then(|&id| pmtiles_id2tile(id).ok()).map_or(0, |tile| tile.z());
+		let (min_zoom, max_zoom) = (zoom_of(ids.first()), zoom_of(ids.last()));
+		let bounds = bounds(&ids);
+
+		let root_offset = HEADER_LEN as u64;
+		let metadata_offset = root_offset + root.len() as u64;
+		let leaf_offset = metadata_offset + metadata.len() as u64;
+		let data_offset = leaf_offset + leaves.len() as u64;
+		let header = Header {
+			root_offset,
+			root_length: root.len() as u64,
+			metadata_offset,
+			metadata_length: metadata.len() as u64,
+			leaf_offset,
+			leaf_length: leaves.len() as u64,
+			data_offset,
+			data_length: data.len() as u64,
+			addressed_tiles: ids.len() as u64,
+			tile_entries: entries.len() as u64,
+			tile_contents: offsets.len() as u64,
+			clustered: true,
+			internal_compression: self.internal_compression,
+			tile_compression: self.tile_compression,
+			tile_type: self.tile_type,
+			min_zoom,
+			max_zoom,
+			bounds,
+			center_zoom: min_zoom,
+			center: ((bounds.west + bounds.east) / 2f64, (bounds.south + bounds.north) / 2f64),
+		};
+
+		// Parse the header back, so that the returned coordinates are rounded as stored.
+		let header = header.to_bytes();
+		out.write_all(&header)?;
+		out.write_all(&root)?;
+		out.write_all(&metadata)?;
+		out.write_all(&leaves)?;
+		out.write_all(&data)?;
+		out.flush()?;
+		Header::from_bytes(&header)
+	}
+
+	/// Encode the root directory and, if it does not fit on its own, the leaf directories.
+	fn build_directories(&self, entries: &[Entry]) -> Result<(Vec<u8>, Vec<u8>), Error> {
+		let root = compress(directory::encode(entries), self.internal_compression)?;
+		if HEADER_LEN + root.len() <= ROOT_MAX_LEN {
+			return Ok((root, Vec::new()));
+		}
+
+		let mut leaf_size = 4096;
+		loop {
+			let mut leaves = Vec::new();
+			let mut root_entries = Vec::new();
+			for chunk in entries.chunks(leaf_size) {
+				let leaf = compress(directory::encode(chunk), self.internal_compression)?;
+				root_entries.push(Entry {
+					tile_id: chunk[0].tile_id,
+					offset: leaves.len() as u64,
+					length: leaf.len().try_into().map_err(|_| Error::TooLarge)?,
+					run_length: 0,
+				});
+				leaves.extend_from_slice(&leaf);
+			}
+			let root = compress(directory::encode(&root_entries), self.internal_compression)?;
+			if HEADER_LEN + root.len() <= ROOT_MAX_LEN {
+				return Ok((root, leaves));
+			}
+			leaf_size *= 2;
+		}
+	}
+}
+
+/// Lon/lat bounds of the tiles at every zoom level.
+fn bounds(ids: &[u64]) -> Bounds {
+	let mut extents: HashMap<u8, (u32, u32, u32, u32)> = HashMap::new();
+	for tile in ids.iter().filter_map(|&id| pmtiles_id2tile(id).ok()) {
+		let (x, y) = (tile.x(), tile.y());
+		extents
+			.entry(tile.z())
+			.and_modify(|(min_x, min_y, max_x, max_y)| {
+				(*min_x, *min_y, *max_x, *max_y) = ((*min_x).min(x), (*min_y).min(y), (*max_x).max(x), (*max_y).max(y));
+			})
+			.or_insert((x, y, x, y));
+	}
+	extents
+		.into_iter()
+		.map(|(zoom, (min_x, min_y, max_x, max_y))| {
+			let (west, north) = tile2lonlat(min_x, min_y, zoom);
+			let (east, south) = tile2lonlat(max_x + 1, max_y + 1, zoom);
+			Bounds { west, south, east, north }
+		})
+		.reduce(|a, b| Bounds {
+			west: a.west.min(b.west),
+			south: a.south.min(b.south),
+			east: a.east.max(b.east),
+			north: a.north.max(b.north),
+		})
+		.unwrap_or(Bounds::new(0f64, 0f64, 0f64, 0f64))
+}
+
+#[cfg(test)]
+mod tests {
+	use std::io::Cursor;
+
+	use super::*;
+	use crate::pmtiles::Reader;
+
+	#[test]
+	fn test_writer() {
+		let mut writer = Writer::new(TileType::Png, Compression::None);
+		writer.set_metadata(r#"{"name":"test"}"#);
+		writer.add_tile(Tile::new(1, 1, 1).unwrap(), b"water".to_vec());
+		writer.add_tile(Tile::new(0, 0, 0).unwrap(), b"world".to_vec());
+		writer.add_tile(Tile::new(0, 0, 1).unwrap(), b"water".to_vec());
+		writer.add_tile(Tile::new(0, 1, 1).unwrap(), b"water".to_vec());
+		writer.add_tile(Tile::new(1, 0, 1).unwrap(), b"land".to_vec());
+		writer.add_tile(Tile::new(1, 0, 1).unwrap(), b"land!".to_vec());
+		assert_eq!(writer.len(), 5);
+
+		let mut archive = Vec::new();
+		let header = writer.finish(&mut archive).unwrap();
+		assert_eq!((header.addressed_tiles, header.tile_entries, header.tile_contents), (5, 3, 3));
+		assert_eq!((header.min_zoom, header.max_zoom), (0, 1));
+		assert_eq!(header.leaf_length, 0);
+		assert_eq!(header.data_length, 15);
+
+		let mut reader = Reader::new(Cursor::new(archive)).unwrap();
+		assert_eq!(reader.header(), &header);
+		assert_eq!(reader.metadata().unwrap(), r#"{"name":"test"}"#);
+		assert_eq!(reader.get_tile(Tile::new(0, 0, 0).unwrap()).unwrap().as_deref(), Some(&b"world"[..]));
+		assert_eq!(reader.get_tile(Tile::new(0, 1, 1).unwrap()).unwrap().as_deref(), Some(&b"water"[..]));
+		assert_eq!(reader.get_tile(Tile::new(1, 1, 1).unwrap()).unwrap().as_deref(), Some(&b"water"[..]));
+		assert_eq!(reader.get_tile(Tile::new(1, 0, 1).unwrap()).unwrap().as_deref(), Some(&b"land!"[..]));
+		assert_eq!(reader.get_tile(Tile::new(0, 0, 2).unwrap()).unwrap(), None);
+	}
+
+	#[test]
+	fn test_writer_leaves() {
+		let mut writer = Writer::new(TileType::Mvt, Compression::None);
+		writer.set_internal_compression(Compression::None);
+		for tile in Tile::new(0, 0, 0).unwrap().descendants(7) {
+			writer.add_tile(tile, tile.to_pmtiles_id().to_le_bytes().to_vec());
+		}
+		let mut archive = Vec::new();
+		let header = writer.finish(&mut archive).unwrap();
+		assert!(header.leaf_length > 0);
+		assert!(HEADER_LEN as u64 + header.root_length <= ROOT_MAX_LEN as u64);
+		assert_eq!(header.bounds.to_array(), [-180.0, -85.0511288, 180.0, 85.0511288]);
+
+		let mut reader = Reader::new(Cursor::new(archive)).unwrap();
+		for t in [Tile::new(0, 0, 7).unwrap(), Tile::new(127, 127, 7).unwrap(), Tile::new(64, 3, 7).unwrap()] {
+			assert_eq!(reader.get_tile(t).unwrap(), Some(t.to_pmtiles_id().to_le_bytes().to_vec()));
+		}
+	}
+
+	#[test]
+	fn test_writer_bounds() {
+		// The bounds cover the tiles of every zoom level, not only those of the highest one.
+		let mut writer = Writer::new(TileType::Png, Compression::None);
+		writer.add_tile(Tile::new(0, 0, 0).unwrap(), b"world".to_vec());
+		for tile in Tile::new(0, 0, 0).unwrap().descendants_through(3) {
+			writer.add_tile(tile, b"world".to_vec());
+		}
+		writer.add_tile(Tile::new(100, 30, 10).unwrap(), b"detail".to_vec());
+		let header = writer.finish(Vec::new()).unwrap();
+		assert_eq!((header.min_zoom, header.max_zoom), (0, 10));
+		assert_eq!(header.bounds.to_array(), [-180.0, -85.0511288, 180.0, 85.0511288]);
+		assert_eq!(header.center, (0.0, 0.0));
+
+		let mut writer = Writer::new(TileType::Png, Compression::None);
+		writer.add_tile(Tile::new(1, 0, 1).unwrap(), b"north-east".to_vec());
+		writer.add_tile(Tile::new(0, 3, 2).unwrap(), b"south-west".to_vec());
+		let header = writer.finish(Vec::new()).unwrap();
+		assert_eq!(header.bounds.to_array(), [-180.0, -85.0511288, 180.0, 85.0511288]);
+	}
+
+	#[test]
+	fn test_writer_empty() {
+		let mut archive = Vec::new();
+		let header = Writer::new(TileType::Unknown, Compression::Unknown).finish(&mut archive).unwrap();
+		assert_eq!(header.addressed_tiles, 0);
+		let mut reader = Reader::new(Cursor::new(archive)).unwrap();
+		assert_eq!(reader.get_tile(Tile::new(0, 0, 0).unwrap()).unwrap(), None);
+	}
+}
@@ -0,0 +1,130 @@
+use crate::{Tile, TileError, MAX_ZOOM};
+
+/// Convert a Web Mercator tile to a Bing Maps quadkey.
+///
+/// The quadkey has one digit per zoom level, so the tile at zoom level 0 is the empty string.
+/// See https://learn.microsoft.com/en-us/bingmaps/articles/bing-maps-tile-system for details.
+///
+/// # Arguments
+///
+/// * `x`    - X tile coordinate
+/// * `y`    - Y tile coordinate
+/// * `zoom` - zoom level
+pub fn tile2quadkey(x: u32, y: u32, zoom: u8) -> String {
+	(1..=zoom)
+		.rev()
+		.map(|i| {
+			let mask = 1u64.checked_shl((i - 1).into()).unwrap_or(0);
+			let mut digit = b'0';
+			if x as u64 & mask != 0 {
+				digit += 1;
+			}
+			if y as u64 & mask != 0 {
+				digit += 2;
+			}
+			digit as char
+		})
+		.collect()
+}
+
+/// Convert a Bing Maps quadkey to a Web Mercator tile.
+///
+/// The zoom level of the tile is the length of the quadkey.
+///
+/// # Arguments
+///
+/// * `quadkey` - quadkey made of the digits `0` to `3`, at most [`MAX_ZOOM`] long
+pub fn quadkey2tile(quadkey: &str) -> Result<Tile, TileError> {
+	if quadkey.len() > MAX_ZOOM as usize {
+		return Err(TileError::QuadkeyTooLong(quadkey.len()));
+	}
+
+	let (mut x, mut y) = (0u32, 0u32);
+	for c in quadkey.chars() {
+		let digit = match c {
+			'0'..='3' => c as u32 - '0' as u32,
+			_ => return Err(TileError::InvalidQuadkeyDigit(c)),
+		};
+		x = (x << 1) | (digit & 1);
+		y = (y << 1) | (digit >> 1);
+	}
+	Tile::new(x, y, quadkey.len() as u8)
+}
+
+/// Quadkey of the tile onto which the given tile is merged when zooming out.
+///
+/// This is the quadkey without its last digit, matching [`crate::zoom_out`].
+/// Returns `None` for the empty quadkey at zoom level 0.
+///
+/// # Arguments
+///
+/// * `quadkey` - quadkey of the current tile
+pub fn quadkey_parent(quadkey: &str) -> Option<&str> {
+	let mut chars = quadkey.chars();
+	chars.next_back()?;
+	Some(chars.as_str())
+}
+
+/// Quadkeys of the 4 tiles onto which the given tile is split out when zooming in.
+///
+/// The children are the quadkey followed by `0` to `3`, in the same order as [`crate::zoom_in`].
+///
+/// # Arguments
+///
+/// * `quadkey` - quadkey of the current tile
+pub fn quadkey_children(quadkey: &str) -> [String; 4] {
+	['0', '1', '2', '3'].map(|digit| format!("{quadkey}{digit}"))
+}
+
+impl Tile {
+	/// Bing Maps quadkey of this tile, see [`tile2quadkey`].
+	pub fn to_quadkey(&self) -> String {
+		tile2quadkey(self.x(), self.y(), self.z())
+	}
+
+	/// Create a tile from a Bing Maps quadkey, see [`quadkey2tile`].
+	pub fn from_quadkey(quadkey: &str) -> Result<Self, TileError> {
+		quadkey2tile(quadkey)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{zoom_in, zoom_out};
+
+	#[test]
+	fn test_quadkey() {
+		assert_eq!(tile2quadkey(3, 5, 3), "213");
+		assert_eq!(tile2quadkey(0, 0, 0), "");
+		assert_eq!(tile2quadkey(4376, 2932, 13), "1202302231200");
+		assert_eq!(tile2quadkey(1, 2, 255), format!("{}21", "0".repeat(253)));
+		assert_eq!(quadkey2tile("213").map(<(u32, u32, u8)>::from), Ok((3, 5, 3)));
+		assert_eq!(quadkey2tile("").map(<(u32, u32, u8)>::from), Ok((0, 0, 0)));
+
+		let tile = Tile::new(u32::MAX >> 1, 12345, MAX_ZOOM).unwrap();
+		assert_eq!(Tile::from_quadkey(&tile.to_quadkey()), Ok(tile));
+	}
+
+	#[test]
+	fn test_quadkey_errors() {
+		assert_eq!(quadkey2tile("0124"), Err(TileError::InvalidQuadkeyDigit('4')));
+		assert_eq!(quadkey2tile("01a"), Err(TileError::InvalidQuadkeyDigit('a')));
+		assert_eq!(quadkey2tile(&"0".repeat(32)), Err(TileError::QuadkeyTooLong(32)));
+	}
+
+	#[test]
+	fn test_quadkey_zoom() {
+		assert_eq!(quadkey_parent("213"), Some("21"));
+		assert_eq!(quadkey_parent(""), None);
+		assert_eq!(quadkey2tile("21").map(<(u32, u32, u8)>::from), Ok((1, 2, 2)));
+		assert_eq!(zoom_out(3, 5), (1, 2));
+
+		let (a, b, c, d) = zoom_in(3, 5);
+		let children = quadkey_children("213").map(|q| {
+			let tile = quadkey2tile(&q).unwrap();
+			(tile.x(), tile.y())
+		});
+		assert_eq!(children, [a, b, c, d]);
+	}
+}
@@ -0,0 +1,264 @@
+use crate::cover::cell_span;
+use crate::{lonlat2tile_frac, Bounds, Tile, TileError, MAX_LATITUDE, MAX_ZOOM};
+
+/// Rectangular range of Web Mercator tiles at a single zoom level, with inclusive bounds.
+///
+/// Ranges are never empty, and iterate over their tiles in row-major order
+/// (west to east, then north to south).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TileRange {
+	min_x: u32,
+	min_y: u32,
+	max_x: u32,
+	max_y: u32,
+	zoom: u8,
+}
+
+impl TileRange {
+	/// Create a tile range, checking that the coordinates are valid for the zoom level.
+	///
+	/// # Arguments
+	///
+	/// * `min_x` - X coordinate of the western column
+	/// * `min_y` - Y coordinate of the northern row
+	/// * `max_x` - X coordinate of the eastern column
+	/// * `max_y` - Y coordinate of the southern row
+	/// * `zoom`  - zoom level
+	pub fn new(min_x: u32, min_y: u32, max_x: u32, max_y: u32, zoom: u8) -> Result<Self, TileError> {
+		Tile::new(min_x, min_y, zoom)?;
+		Tile::new(max_x, max_y, zoom)?;
+		if min_x > max_x || min_y > max_y {
+			return Err(TileError::InvalidRange);
+		}
+		Ok(Self { min_x, min_y, max_x, max_y, zoom })
+	}
+
+	/// Range made of a single tile.
+	pub fn from_tile(tile: Tile) -> Self {
+		let (x, y, zoom) = tile.into();
+		Self { min_x: x, min_y: y, max_x: x, max_y: y, zoom }
+	}
+
+	/// Find the tiles covering a lon/lat bounding box at a given zoom level.
+	///
+	/// A box whose eastern or southern edge lies exactly on a tile boundary does not include
+	/// the tiles beyond that boundary. A box whose western edge is east of its eastern edge
+	/// crosses the antimeridian, and is covered by two ranges, one on each side.
+	/// Latitudes are clamped to [`MAX_LATITUDE`].
+	///
+	/// # Arguments
+	///
+	/// * `bounds` - bounding box, in degrees
+	/// * `zoom`   - zoom level
+	pub fn from_bounds(bounds: &Bounds, zoom: u8) -> Result<Vec<Self>, TileError> {
+		if zoom > MAX_ZOOM {
+			return Err(TileError::InvalidZoom(zoom));
+		}
+		let Bounds { west, south, east, north } = *bounds;
+		for (lon, lat) in [(west, south), (east, north)] {
+			if !lon.is_finite() || !lat.is_finite() {
+				return Err(TileError::NonFinite { lon, lat });
+			}
+			if !(-180f64..=180f64).contains(&lon) {
+				return Err(TileError::LongitudeOutOfRange(lon));
+			}
+		}
+		if south > north {
+			return Err(TileError::InvalidRange);
+		}
+
+		let south = south.clamp(-MAX_LATITUDE, MAX_LATITUDE);
+		let north = north.clamp(-MAX_LATITUDE, MAX_LATITUDE);
+		if west <= east {
+			Ok(vec![Self::covering(west, south, east, north, zoom)])
+		} else if east == -180f64 {
+			Ok(vec![Self::covering(west, south, 180f64, north, zoom)])
+		} else {
+			Ok(vec![Self::covering(west, south, 180f64, north, zoom), Self::covering(-180f64, south, east, north, zoom)])
+		}
+	}
+
+	fn covering(west: f64, south: f64, east: f64, north: f64, zoom: u8) -> Self {
+		let max = (1i64 << zoom) - 1;
+		let (x0, y0) = lonlat2tile_frac(west, north, zoom);
+		let (x1, y1) = lonlat2tile_frac(east, south, zoom);
+		let (min_x, max_x) = cell_span(x0, x1, max);
+		let (min_y, max_y) = cell_span(y0, y1, max);
+		Self { min_x, min_y, max_x, max_y, zoom }
+	}
+
+	/// X coordinate of the western column.
+	pub fn min_x(&self) -> u32 {
+		self.min_x
+	}
+
+	/// Y coordinate of the northern row.
+	pub fn min_y(&self) -> u32 {
+		self.min_y
+	}
+
+	/// X coordinate of the eastern column.
+	pub fn max_x(&self) -> u32 {
+		self.max_x
+	}
+
+	/// Y coordinate of the southern row.
+	pub fn max_y(&self) -> u32 {
+		self.max_y
+	}
+
+	/// Zoom level.
+	pub fn zoom(&self) -> u8 {
+		self.zoom
+	}
+
+	/// Number of columns.
+	pub fn width(&self) -> u32 {
+		self.max_x - self.min_x + 1
+	}
+
+	/// Number of rows.
+	pub fn height(&self) -> u32 {
+		self.max_y - self.min_y + 1
+	}
+
+	/// Number of tiles in the range.
+	pub fn len(&self) -> u64 {
+		self.width() as u64 * self.height() as u64
+	}
+
+	/// Whether the range contains no tiles, which is never the case.
+	pub fn is_empty(&self) -> bool {
+		false
+	}
+
+	/// Whether the range contains the given tile.
+	pub fn contains(&self, tile: &Tile) -> bool {
+		tile.z() == self.zoom
+			&& (self.min_x..=self.max_x).contains(&tile.x())
+			&& (self.min_y..=self.max_y).contains(&tile.y())
+	}
+
+	/// Iterate over the tiles of the range in row-major order.
+	pub fn iter(&self) -> TileRangeIter {
+		TileRangeIter { range: *self, x: self.min_x, y: self.min_y, done: false }
+	}
+}
+
+impl IntoIterator for TileRange {
+	type Item = Tile;
+	type IntoIter = TileRangeIter;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.iter()
+	}
+}
+
+impl IntoIterator for &TileRange {
+	type Item = Tile;
+	type IntoIter = TileRangeIter;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.iter()
+	}
+}
+
+/// Iterator over the tiles of a [`TileRange`], in row-major order.
+#[derive(Debug, Clone)]
+pub struct TileRangeIter {
+	range: TileRange,
+	x: u32,
+	y: u32,
+	done: bool,
+}
+
+impl TileRangeIter {
+	fn remaining(&self) -> u64 {
+		if self.done {
+			return 0;
+		}
+		let rows_after = (self.range.max_y - self.y) as u64;
+		rows_after * self.range.width() as u64 + (self.range.max_x - self.x + 1) as u64
+	}
+}
+
+impl Iterator for TileRangeIter {
+	type Item = Tile;
+
+	fn next(&mut self) -> Option<Tile> {
+		if self.done {
+			return None;
+		}
+		let tile = Tile::new(self.x, self.y, self.range.zoom).ok()?;
+		if self.x < self.range.max_x {
+			self.x += 1;
+		} else if self.y < self.range.max_y {
+			self.x = self.range.min_x;
+			self.y += 1;
+		} else {
+			self.done = true;
+		}
+		Some(tile)
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		match usize::try_from(self.remaining()) {
+			Ok(n) => (n, Some(n)),
+			Err(_) => (usize::MAX, None),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tile_bounds;
+
+	fn corners(range: &TileRange) -> (u32, u32, u32, u32) {
+		(range.min_x(), range.min_y(), range.max_x(), range.max_y())
+	}
+
+	#[test]
+	fn test_range() {
+		let range = TileRange::new(1, 2, 3, 3, 2).unwrap();
+		assert_eq!(range.len(), 6);
+		assert!(range.contains(&Tile::new(3, 2, 2).unwrap()));
+		assert!(!range.contains(&Tile::new(0, 2, 2).unwrap()));
+		assert!(!range.contains(&Tile::new(1, 2, 3).unwrap()));
+
+		let tiles: Vec<_> = range.iter().map(|t| (t.x(), t.y())).collect();
+		assert_eq!(tiles, [(1, 2), (2, 2), (3, 2), (1, 3), (2, 3), (3, 3)]);
+		assert_eq!(range.iter().size_hint(), (6, Some(6)));
+
+		assert_eq!(TileRange::new(2, 0, 1, 0, 2), Err(TileError::InvalidRange));
+		assert!(TileRange::new(0, 0, 4, 0, 2).is_err());
+	}
+
+	#[test]
+	fn test_from_bounds() {
+		let ranges = TileRange::from_bounds(&Bounds::new(-180.0, -90.0, 180.0, 90.0), 2).unwrap();
+		assert_eq!(ranges.iter().map(corners).collect::<Vec<_>>(), [(0, 0, 3, 3)]);
+
+		let ranges = TileRange::from_bounds(&Bounds::new(-10.0, -10.0, 0.0, 0.0), 1).unwrap();
+		assert_eq!(ranges.iter().map(corners).collect::<Vec<_>>(), [(0, 1, 0, 1)]);
+
+		let ranges = TileRange::from_bounds(&tile_bounds(4376, 2932, 13), 13).unwrap();
+		assert_eq!(ranges.iter().map(corners).collect::<Vec<_>>(), [(4376, 2932, 4376, 2932)]);
+
+		let ranges = TileRange::from_bounds(&Bounds::new(5.0, 5.0, 5.0, 5.0), 3).unwrap();
+		assert_eq!(ranges[0].len(), 1);
+
+		assert_eq!(TileRange::from_bounds(&Bounds::new(0.0, 1.0, 1.0, 0.0), 3), Err(TileError::InvalidRange));
+		assert!(TileRange::from_bounds(&Bounds::new(f64::NAN, 0.0, 1.0, 1.0), 3).is_err());
+	}
+
+	#[test]
+	fn test_from_bounds_antimeridian() {
+		let ranges = TileRange::from_bounds(&Bounds::new(170.0, -10.0, -170.0, 10.0), 3).unwrap();
+		assert_eq!(ranges.iter().map(corners).collect::<Vec<_>>(), [(7, 3, 7, 4), (0, 3, 0, 4)]);
+		assert_eq!(ranges.iter().map(TileRange::len).sum::<u64>(), 4);
+
+		let ranges = TileRange::from_bounds(&Bounds::new(90.0, 0.0, -180.0, 10.0), 2).unwrap();
+		assert_eq!(ranges.iter().map(corners).collect::<Vec<_>>(), [(3, 1, 3, 1)]);
+	}
+}
@@ -0,0 +1,95 @@
+use crate::MAX_EXTENT;
+
+/// Size of a pixel in meters as standardized by OGC for WMS and WMTS (0.28 mm).
+pub const OGC_PIXEL_SIZE: f64 = 0.00028;
+
+/// Screen resolution implied by [`OGC_PIXEL_SIZE`], in dots per inch (about 90.7).
+pub const OGC_DPI: f64 = INCH / OGC_PIXEL_SIZE;
+
+/// Length of an inch, in meters.
+const INCH: f64 = 0.0254;
+
+/// Ground resolution at a given latitude and zoom level, in meters per pixel.
+///
+/// At the equator this is also the resolution in projected Web Mercator meters,
+/// as used for WMTS tile matrices.
+///
+/// # Arguments
+///
+/// * `lat`       - latitude coordinate (N-S), in degrees
+/// * `zoom`      - zoom level
+/// * `tile_size` - tile size, in pixels
+pub fn ground_resolution(lat: f64, zoom: u8, tile_size: u32) -> f64 {
+	lat.to_radians().cos() * 2f64 * MAX_EXTENT / (tile_size as f64 * 2f64.powf(zoom as f64))
+}
+
+/// Map scale denominator at a given latitude and zoom level.
+///
+/// A scale denominator of 25000 means a scale of 1:25000 on a screen or print
+/// with the given resolution. Use [`OGC_DPI`] for the scale denominators used by WMS and WMTS.
+///
+/// # Arguments
+///
+/// * `lat`       - latitude coordinate (N-S), in degrees
+/// * `zoom`      - zoom level
+/// * `tile_size` - tile size, in pixels
+/// * `dpi`       - resolution of the output, in dots per inch
+pub fn scale_denominator(lat: f64, zoom: u8, tile_size: u32, dpi: f64) -> f64 {
+	ground_resolution(lat, zoom, tile_size) * dpi / INCH
+}
+
+/// Fractional zoom level at which the ground resolution at a given latitude is reached.
+///
+/// This is the inverse of [`ground_resolution`]. Round the result down to get a zoom level
+/// at least as detailed as requested, or up to get one at most as detailed.
+///
+/// # Arguments
+///
+/// * `resolution` - ground resolution, in meters per pixel
+/// * `lat`        - latitude coordinate (N-S), in degrees
+/// * `tile_size`  - tile size, in pixels
+pub fn zoom_for_resolution(resolution: f64, lat: f64, tile_size: u32) -> f64 {
+	(lat.to_radians().cos() * 2f64 * MAX_EXTENT / (tile_size as f64 * resolution)).log2()
+}
+
+/// Fractional zoom level at which the scale denominator at a given latitude is reached.
+///
+/// This is the inverse of [`scale_denominator`].
+///
+/// # Arguments
+///
+/// * `scale`     - scale denominator
+/// * `lat`       - latitude coordinate (N-S), in degrees
+/// * `tile_size` - tile size, in pixels
+/// * `dpi`       - resolution of the output, in dots per inch
+pub fn zoom_for_scale_denominator(scale: f64, lat: f64, tile_size: u32, dpi: f64) -> f64 {
+	zoom_for_resolution(scale * INCH / dpi, lat, tile_size)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::TILE_SIZE;
+
+	fn close(a: f64, b: f64) -> bool {
+		(a - b).abs() < 1e-6 * b.abs().max(1.0)
+	}
+
+	#[test]
+	fn test_resolution() {
+		assert!(close(ground_resolution(0.0, 0, TILE_SIZE), 156543.03392804097));
+		assert!(close(ground_resolution(0.0, 0, 512), 78271.51696402048));
+		assert!(close(ground_resolution(60.0, 1, TILE_SIZE), 156543.03392804097 / 4.0));
+		assert!(close(zoom_for_resolution(ground_resolution(45.0, 13, 256), 45.0, 256), 13.0));
+		assert!(close(zoom_for_resolution(1.0, 0.0, 256), 17.256199796589126));
+	}
+
+	#[test]
+	fn test_scale() {
+		// WebMercatorQuad scale denominator of the first tile matrix.
+		assert!(close(scale_denominator(0.0, 0, TILE_SIZE, OGC_DPI), 559082264.0287178));
+		assert!(close(scale_denominator(0.0, 18, TILE_SIZE, OGC_DPI), 2132.729583849784));
+		assert!(close(zoom_for_scale_denominator(2132.729583849784, 0.0, TILE_SIZE, OGC_DPI), 18.0));
+		assert!(close(zoom_for_scale_denominator(scale_denominator(52.0, 7, 512, 300.0), 52.0, 512, 300.0), 7.0));
+	}
+}
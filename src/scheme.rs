@@ -0,0 +1,138 @@
+use std::fmt;
+
+use crate::{lonlat2tile, tile2lonlat, Tile, TileError};
+
+/// Tile numbering scheme, describing in which direction the Y coordinate grows.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "lowercase"))]
+pub enum Scheme {
+	/// XYZ or "slippy map" scheme used by OpenStreetMap and Google, with Y = 0 at the top (north).
+	#[default]
+	Xyz,
+	/// Tile Map Service scheme used by MBTiles and GeoServer, with Y = 0 at the bottom (south).
+	Tms,
+}
+
+impl Scheme {
+	/// Lowercase name of the scheme, as used by TileJSON.
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Scheme::Xyz => "xyz",
+			Scheme::Tms => "tms",
+		}
+	}
+}
+
+impl fmt::Display for Scheme {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.as_str())
+	}
+}
+
+/// Flip a Y tile coordinate between the XYZ and the TMS scheme.
+///
+/// The operation is its own inverse: it computes `2^zoom - 1 - y`.
+///
+/// # Arguments
+///
+/// * `y`    - Y tile coordinate
+/// * `zoom` - zoom level
+pub fn flip_y(y: u32, zoom: u8) -> u32 {
+	1u64.checked_shl(zoom.into()).unwrap_or(0).wrapping_sub(1).wrapping_sub(y as u64) as u32
+}
+
+/// Convert a Y tile coordinate from one scheme to another.
+///
+/// # Arguments
+///
+/// * `y`    - Y tile coordinate in the `from` scheme
+/// * `zoom` - zoom level
+/// * `from` - scheme of the given coordinate
+/// * `to`   - scheme of the returned coordinate
+pub fn convert_y(y: u32, zoom: u8, from: Scheme, to: Scheme) -> u32 {
+	if from == to {
+		y
+	} else {
+		flip_y(y, zoom)
+	}
+}
+
+/// Convert lon/lat coordinates to a tile of the given scheme at a given zoom level.
+///
+/// # Arguments
+///
+/// * `lon`    - longitude coordinate (W-E), in degrees
+/// * `lat`    - latitude  coordinate (N-S), in degrees
+/// * `zoom`   - zoom level
+/// * `scheme` - scheme of the returned tile
+pub fn lonlat2tile_scheme(lon: f64, lat: f64, zoom: u8, scheme: Scheme) -> (u32, u32) {
+	let (x, y) = lonlat2tile(lon, lat, zoom);
+	(x, convert_y(y, zoom, Scheme::Xyz, scheme))
+}
+
+/// Convert a tile of the given scheme to lon/lat coordinates at a given zoom level.
+///
+/// The returned coordinates are those of the north-west corner of the tile, whatever the scheme.
+///
+/// # Arguments
+///
+/// * `x`      - X tile coordinate
+/// * `y`      - Y tile coordinate in the given scheme
+/// * `zoom`   - zoom level
+/// * `scheme` - scheme of the given tile
+pub fn tile2lonlat_scheme(x: u32, y: u32, zoom: u8, scheme: Scheme) -> (f64, f64) {
+	tile2lonlat(x, convert_y(y, zoom, scheme, Scheme::Xyz), zoom)
+}
+
+impl Tile {
+	/// Create a tile from coordinates in the given scheme.
+	///
+	/// # Arguments
+	///
+	/// * `x`      - X tile coordinate
+	/// * `y`      - Y tile coordinate in the given scheme
+	/// * `z`      - zoom level
+	/// * `scheme` - scheme of the given coordinates
+	pub fn with_scheme(x: u32, y: u32, z: u8, scheme: Scheme) -> Result<Self, TileError> {
+		// Validate first so that flipping an out-of-range Y cannot wrap into a valid one.
+		let tile = Tile::new(x, y, z)?;
+		Tile::new(x, convert_y(tile.y(), z, scheme, Scheme::Xyz), z)
+	}
+
+	/// Y tile coordinate in the given scheme.
+	pub fn y_in(&self, scheme: Scheme) -> u32 {
+		convert_y(self.y(), self.z(), Scheme::Xyz, scheme)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_flip() {
+		assert_eq!(flip_y(2932, 13), 5259);
+		assert_eq!(flip_y(5259, 13), 2932);
+		assert_eq!(flip_y(0, 0), 0);
+		assert_eq!(flip_y(0, 32), u32::MAX);
+		assert_eq!(flip_y(0, 64), u32::MAX);
+		assert_eq!(flip_y(5, 255), u32::MAX - 5);
+		assert_eq!(convert_y(1, 2, Scheme::Tms, Scheme::Tms), 1);
+		assert_eq!(convert_y(1, 2, Scheme::Xyz, Scheme::Tms), 2);
+	}
+
+	#[test]
+	fn test_scheme_lonlat() {
+		assert_eq!(lonlat2tile_scheme(12.3046875, 45.460130637921, 13, Scheme::Xyz), (4376, 2932));
+		assert_eq!(lonlat2tile_scheme(12.3046875, 45.460130637921, 13, Scheme::Tms), (4376, 5259));
+		assert_eq!(tile2lonlat_scheme(4376, 5259, 13, Scheme::Tms), tile2lonlat(4376, 2932, 13));
+	}
+
+	#[test]
+	fn test_scheme_tile() {
+		let tile = Tile::with_scheme(4376, 5259, 13, Scheme::Tms).unwrap();
+		assert_eq!((tile.y(), tile.y_in(Scheme::Xyz), tile.y_in(Scheme::Tms)), (2932, 2932, 5259));
+		assert!(Tile::with_scheme(0, 2, 1, Scheme::Tms).is_err());
+		assert_eq!(Scheme::default().to_string(), "xyz");
+	}
+}
@@ -0,0 +1,197 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::{tile2lonlat, try_lonlat2tile, LonLatPolicy};
+
+/// Highest zoom level a [`Tile`] can be created at.
+///
+/// Coordinates at zoom level `z` are checked to be smaller than `2^z`, and this bound only fits
+/// in the `u32` coordinates up to zoom level 31.
+pub const MAX_ZOOM: u8 = 31;
+
+/// Error returned when a tile cannot be created from the given input.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub enum TileError {
+	/// The zoom level is greater than [`MAX_ZOOM`], or [`crate::MAX_ZOOM_64`] for a [`crate::Tile64`].
+	InvalidZoom(u8),
+	/// The X or Y coordinate is not smaller than `2^z`.
+	OutOfRange { x: u64, y: u64, z: u8 },
+	/// The longitude or latitude is NaN or infinite.
+	NonFinite { lon: f64, lat: f64 },
+	/// The longitude is outside of `[-180, 180]`.
+	LongitudeOutOfRange(f64),
+	/// The latitude is beyond [`crate::MAX_LATITUDE`].
+	LatitudeOutOfRange(f64),
+	/// The quadkey contains a character other than `0` to `3`.
+	InvalidQuadkeyDigit(char),
+	/// The quadkey is longer than [`MAX_ZOOM`] digits.
+	QuadkeyTooLong(usize),
+	/// The minimum of a range or bounding box is greater than its maximum.
+	InvalidRange,
+}
+
+impl fmt::Display for TileError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			TileError::InvalidZoom(z) => write!(f, "zoom level {z} is too large"),
+			TileError::OutOfRange { x, y, z } => write!(f, "tile ({x}, {y}) is out of range at zoom level {z}"),
+			TileError::NonFinite { lon, lat } => write!(f, "coordinates ({lon}, {lat}) are not finite"),
+			TileError::LongitudeOutOfRange(lon) => write!(f, "longitude {lon} is outside of [-180, 180]"),
+			TileError::LatitudeOutOfRange(lat) => write!(f, "latitude {lat} is outside of the Web Mercator bounds"),
+			TileError::InvalidQuadkeyDigit(c) => write!(f, "invalid quadkey digit {c:?}"),
+			TileError::QuadkeyTooLong(len) => write!(f, "quadkey of length {len} is longer than {MAX_ZOOM}"),
+			TileError::InvalidRange => write!(f, "range minimum is greater than its maximum"),
+		}
+	}
+}
+
+impl Error for TileError {}
+
+/// A Web Mercator tile whose coordinates are guaranteed to be valid for its zoom level.
+///
+/// Unlike the bare `(x, y)` tuples used by [`crate::lonlat2tile`] and [`tile2lonlat`],
+/// a `Tile` can only be created through checked constructors, so `x` and `y`
+/// are always smaller than `2^z` and `z` never exceeds [`MAX_ZOOM`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Tile {
+	x: u32,
+	y: u32,
+	z: u8,
+}
+
+impl Tile {
+	/// Create a tile, checking that the coordinates are valid for the zoom level.
+	///
+	/// # Arguments
+	///
+	/// * `x` - X tile coordinate
+	/// * `y` - Y tile coordinate
+	/// * `z` - zoom level
+	pub fn new(x: u32, y: u32, z: u8) -> Result<Self, TileError> {
+		if z > MAX_ZOOM {
+			return Err(TileError::InvalidZoom(z));
+		}
+		let n = 1u32 << z;
+		if x >= n || y >= n {
+			return Err(TileError::OutOfRange { x: x as u64, y: y as u64, z });
+		}
+		Ok(Self { x, y, z })
+	}
+
+	/// Find the tile containing the given lon/lat coordinates at a given zoom level.
+	///
+	/// Coordinates outside of the Web Mercator bounds are rejected,
+	/// see [`try_lonlat2tile`] for other ways of handling them.
+	///
+	/// # Arguments
+	///
+	/// * `lon`  - longitude coordinate (W-E), in degrees
+	/// * `lat`  - latitude  coordinate (N-S), in degrees
+	/// * `zoom` - zoom level
+	pub fn from_lonlat(lon: f64, lat: f64, zoom: u8) -> Result<Self, TileError> {
+		try_lonlat2tile(lon, lat, zoom, LonLatPolicy::Error)
+	}
+
+	/// X tile coordinate.
+	pub fn x(&self) -> u32 {
+		self.x
+	}
+
+	/// Y tile coordinate.
+	pub fn y(&self) -> u32 {
+		self.y
+	}
+
+	/// Zoom level.
+	pub fn z(&self) -> u8 {
+		self.z
+	}
+
+	/// Lon/lat coordinates of the north-west corner of the tile.
+	pub fn to_lonlat(&self) -> (f64, f64) {
+		tile2lonlat(self.x, self.y, self.z)
+	}
+
+	/// Zoom in from this tile.
+	///
+	/// Returns the 4 tiles at the next zoom level, in the same order as [`crate::zoom_in`],
+	/// or `None` if the tile is already at [`MAX_ZOOM`].
+	pub fn zoom_in(&self) -> Option<(Tile, Tile, Tile, Tile)> {
+		if self.z >= MAX_ZOOM {
+			return None;
+		}
+		let (x, y, z) = (2 * self.x, 2 * self.y, self.z + 1);
+		Some((
+			Tile { x, y, z },
+			Tile { x: x + 1, y, z },
+			Tile { x, y: y + 1, z },
+			Tile { x: x + 1, y: y + 1, z },
+		))
+	}
+
+	/// Zoom out from this tile.
+	///
+	/// Returns the tile at the previous zoom level containing this tile,
+	/// or `None` if the tile is at zoom level 0.
+	pub fn zoom_out(&self) -> Option<Tile> {
+		if self.z == 0 {
+			return None;
+		}
+		Some(Tile { x: self.x / 2, y: self.y / 2, z: self.z - 1 })
+	}
+}
+
+impl From<Tile> for (u32, u32, u8) {
+	fn from(tile: Tile) -> Self {
+		(tile.x, tile.y, tile.z)
+	}
+}
+
+impl TryFrom<(u32, u32, u8)> for Tile {
+	type Error = TileError;
+
+	fn try_from((x, y, z): (u32, u32, u8)) -> Result<Self, Self::Error> {
+		Tile::new(x, y, z)
+	}
+}
+
+/// Formats the tile as `z/x/y`, the form used in tile URLs.
+impl fmt::Display for Tile {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}/{}/{}", self.z, self.x, self.y)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_new() {
+		assert_eq!(Tile::new(4376, 2932, 13).map(<(u32, u32, u8)>::from), Ok((4376, 2932, 13)));
+		assert!(Tile::new(0, 0, 0).is_ok());
+		assert!(Tile::new(u32::MAX, u32::MAX, MAX_ZOOM).is_err());
+		assert_eq!(Tile::new(1, 0, 0), Err(TileError::OutOfRange { x: 1, y: 0, z: 0 }));
+		assert_eq!(Tile::new(0, 0, 32), Err(TileError::InvalidZoom(32)));
+	}
+
+	#[test]
+	fn test_lonlat() {
+		let tile = Tile::from_lonlat(12.3046875, 45.460130637921, 13).unwrap();
+		assert_eq!((tile.x(), tile.y(), tile.z()), (4376, 2932, 13));
+		assert_eq!(tile.to_lonlat(), (12.3046875, 45.460130637921));
+		assert!(Tile::from_lonlat(123456.789, 123456.789, 0).is_err());
+		assert!(Tile::from_lonlat(0.0, 0.0, 40).is_err());
+	}
+
+	#[test]
+	fn test_zoom() {
+		let tile = Tile::new(1, 1, 1).unwrap();
+		let (a, b, c, d) = tile.zoom_in().unwrap();
+		assert_eq!([a, b, c, d].map(<(u32, u32, u8)>::from), [(2, 2, 2), (3, 2, 2), (2, 3, 2), (3, 3, 2)]);
+		assert_eq!(a.zoom_out(), Some(tile));
+		assert_eq!(Tile::new(0, 0, 0).unwrap().zoom_out(), None);
+		assert_eq!(Tile::new(0, 0, MAX_ZOOM).unwrap().zoom_in(), None);
+	}
+}
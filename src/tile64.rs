@@ -0,0 +1,210 @@
+use crate::{apply_policy, lonlat2tile_frac, tile2lonlat_frac, LonLatPolicy, Tile, TileError, MAX_ZOOM};
+
+/// Highest zoom level a [`Tile64`] can be created at.
+///
+/// The number of tiles along each axis, `1 << z`, is computed in a `u64` and would overflow
+/// from zoom level 64.
+///
+/// Lon/lat coordinates are stored in an `f64`, whose 53 bits of precision are not enough
+/// to tell apart neighboring tiles beyond zoom level 52 or so. Deeper tiles can still be
+/// reached exactly by zooming in from a shallower tile.
+pub const MAX_ZOOM_64: u8 = 63;
+
+/// Convert lon/lat coordinates to a Web Mercator tile at a given zoom level, with `u64` coordinates.
+///
+/// Same as [`crate::lonlat2tile`], for zoom levels up to [`MAX_ZOOM_64`].
+///
+/// # Arguments
+///
+/// * `lon`  - longitude coordinate (W-E), in degrees
+/// * `lat`  - latitude  coordinate (N-S), in degrees
+/// * `zoom` - zoom level
+pub fn lonlat2tile64(lon: f64, lat: f64, zoom: u8) -> (u64, u64) {
+	let (x, y) = lonlat2tile_frac(lon, lat, zoom);
+	(x as u64, y as u64)
+}
+
+/// Convert a Web Mercator tile with `u64` coordinates to lon/lat coordinates at a given zoom level.
+///
+/// Same as [`crate::tile2lonlat`], for zoom levels up to [`MAX_ZOOM_64`].
+///
+/// # Arguments
+///
+/// * `x`    - X tile coordinate
+/// * `y`    - Y tile coordinate
+/// * `zoom` - zoom level
+pub fn tile2lonlat64(x: u64, y: u64, zoom: u8) -> (f64, f64) {
+	tile2lonlat_frac(x as f64, y as f64, zoom)
+}
+
+/// A Web Mercator tile with `u64` coordinates, for zoom levels beyond [`crate::MAX_ZOOM`].
+///
+/// Like [`Tile`], the coordinates are guaranteed to be smaller than `2^z`,
+/// and `z` never exceeds [`MAX_ZOOM_64`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Tile64 {
+	x: u64,
+	y: u64,
+	z: u8,
+}
+
+impl Tile64 {
+	/// Create a tile, checking that the coordinates are valid for the zoom level.
+	///
+	/// # Arguments
+	///
+	/// * `x` - X tile coordinate
+	/// * `y` - Y tile coordinate
+	/// * `z` - zoom level
+	pub fn new(x: u64, y: u64, z: u8) -> Result<Self, TileError> {
+		if z > MAX_ZOOM_64 {
+			return Err(TileError::InvalidZoom(z));
+		}
+		let n = 1u64 << z;
+		if x >= n || y >= n {
+			return Err(TileError::OutOfRange { x, y, z });
+		}
+		Ok(Self { x, y, z })
+	}
+
+	/// Find the tile containing the given lon/lat coordinates at a given zoom level.
+	///
+	/// Coordinates outside of the Web Mercator bounds are rejected.
+	///
+	/// # Arguments
+	///
+	/// * `lon`  - longitude coordinate (W-E), in degrees
+	/// * `lat`  - latitude  coordinate (N-S), in degrees
+	/// * `zoom` - zoom level
+	pub fn from_lonlat(lon: f64, lat: f64, zoom: u8) -> Result<Self, TileError> {
+		if zoom > MAX_ZOOM_64 {
+			return Err(TileError::InvalidZoom(zoom));
+		}
+		let (lon, lat) = apply_policy(lon, lat, LonLatPolicy::Error)?;
+
+		let max = (1u64 << zoom) - 1;
+		let (x, y) = lonlat2tile64(lon, lat, zoom);
+		Self::new(x.min(max), y.min(max), zoom)
+	}
+
+	/// X tile coordinate.
+	pub fn x(&self) -> u64 {
+		self.x
+	}
+
+	/// Y tile coordinate.
+	pub fn y(&self) -> u64 {
+		self.y
+	}
+
+	/// Zoom level.
+	pub fn z(&self) -> u8 {
+		self.z
+	}
+
+	/// Lon/lat coordinates of the north-west corner of the tile.
+	pub fn to_lonlat(&self) -> (f64, f64) {
+		tile2lonlat64(self.x, self.y, self.z)
+	}
+
+	/// Zoom in from this tile.
+	///
+	/// Returns the 4 tiles at the next zoom level, in the same order as [`crate::zoom_in`],
+	/// or `None` if the tile is already at [`MAX_ZOOM_64`].
+	pub fn zoom_in(&self) -> Option<(Tile64, Tile64, Tile64, Tile64)> {
+		if self.z >= MAX_ZOOM_64 {
+			return None;
+		}
+		let (x, y, z) = (self.x.checked_mul(2)?, self.y.checked_mul(2)?, self.z + 1);
+		Some((
+			Tile64 { x, y, z },
+			Tile64 { x: x + 1, y, z },
+			Tile64 { x, y: y + 1, z },
+			Tile64 { x: x + 1, y: y + 1, z },
+		))
+	}
+
+	/// Zoom out from this tile.
+	///
+	/// Returns the tile at the previous zoom level containing this tile,
+	/// or `None` if the tile is at zoom level 0.
+	pub fn zoom_out(&self) -> Option<Tile64> {
+		if self.z == 0 {
+			return None;
+		}
+		Some(Tile64 { x: self.x / 2, y: self.y / 2, z: self.z - 1 })
+	}
+}
+
+impl From<Tile> for Tile64 {
+	fn from(tile: Tile) -> Self {
+		Tile64 { x: tile.x() as u64, y: tile.y() as u64, z: tile.z() }
+	}
+}
+
+impl TryFrom<Tile64> for Tile {
+	type Error = TileError;
+
+	fn try_from(tile: Tile64) -> Result<Self, Self::Error> {
+		if tile.z > MAX_ZOOM {
+			return Err(TileError::InvalidZoom(tile.z));
+		}
+		// Up to MAX_ZOOM the coordinates always fit in a u32.
+		Tile::new(tile.x as u32, tile.y as u32, tile.z)
+	}
+}
+
+impl From<Tile64> for (u64, u64, u8) {
+	fn from(tile: Tile64) -> Self {
+		(tile.x, tile.y, tile.z)
+	}
+}
+
+impl TryFrom<(u64, u64, u8)> for Tile64 {
+	type Error = TileError;
+
+	fn try_from((x, y, z): (u64, u64, u8)) -> Result<Self, Self::Error> {
+		Tile64::new(x, y, z)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::lonlat2tile;
+
+	#[test]
+	fn test_new() {
+		assert!(Tile64::new(u32::MAX as u64, 0, 32).is_ok());
+		assert!(Tile64::new((1 << 63) - 1, (1 << 63) - 1, MAX_ZOOM_64).is_ok());
+		assert_eq!(Tile64::new(1 << 32, 0, 32), Err(TileError::OutOfRange { x: 1 << 32, y: 0, z: 32 }));
+		assert_eq!(Tile64::new(0, 0, 64), Err(TileError::InvalidZoom(64)));
+	}
+
+	#[test]
+	fn test_lonlat() {
+		assert_eq!(lonlat2tile64(12.3046875, 45.460130637921, 13), (4376, 2932));
+		let (x, y) = lonlat2tile(14.016667, 42.683333, 30);
+		let (x64, y64) = lonlat2tile64(14.016667, 42.683333, 40);
+		assert_eq!((x64 >> 10, y64 >> 10), (x as u64, y as u64));
+
+		let tile = Tile64::from_lonlat(180.0, 0.0, MAX_ZOOM_64).unwrap();
+		assert_eq!((tile.x(), tile.y()), ((1 << 63) - 1, 1 << 62));
+		assert_eq!(tile2lonlat64(1 << 35, 1 << 35, 36), (0.0, 0.0));
+		assert!(Tile64::from_lonlat(0.0, 90.0, 40).is_err());
+	}
+
+	#[test]
+	fn test_zoom() {
+		let tile = Tile64::new(u32::MAX as u64, 1, 32).unwrap();
+		let (a, _, _, d) = tile.zoom_in().unwrap();
+		assert_eq!(<(u64, u64, u8)>::from(d), ((u32::MAX as u64) * 2 + 1, 3, 33));
+		assert_eq!(a.zoom_out(), Some(tile));
+		assert_eq!(Tile64::new(0, 0, MAX_ZOOM_64).unwrap().zoom_in(), None);
+
+		let small = Tile::new(3, 5, 3).unwrap();
+		assert_eq!(Tile::try_from(Tile64::from(small)), Ok(small));
+		assert!(Tile::try_from(tile).is_err());
+		assert!(Tile::try_from(Tile64::new(0, 0, MAX_ZOOM + 1).unwrap()).is_err());
+	}
+}
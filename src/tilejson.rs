@@ -0,0 +1,208 @@
+//! Model of [TileJSON 3.0](https://github.com/mapbox/tilejson-spec/tree/master/3.0.0) documents.
+//!
+//! Parsing and generating the JSON encoding requires the `serde` feature.
+
+use std::collections::BTreeMap;
+
+use crate::{Bounds, Scheme, TileRange};
+
+/// Version of the TileJSON specification implemented by [`TileJson`].
+pub const TILEJSON_VERSION: &str = "3.0.0";
+
+/// Highest zoom level allowed by the TileJSON specification, and the default `maxzoom`.
+pub const TILEJSON_MAX_ZOOM: u8 = 30;
+
+/// TileJSON document describing a tileset.
+///
+/// When parsing, `tilejson` and `tiles` are required, and the other missing fields take the
+/// default values of the specification. When generating, optional fields that are not set
+/// and empty lists are left out.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TileJson {
+	/// Version of the TileJSON specification the document follows.
+	pub tilejson: String,
+	/// Tile URL templates, see [`crate::TileUrlTemplate`].
+	pub tiles: Vec<String>,
+	/// Layers of a vector tileset.
+	#[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+	pub vector_layers: Vec<VectorLayer>,
+	/// Attribution to display with the map, as HTML.
+	#[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+	pub attribution: Option<String>,
+	/// Extent of the tileset in degrees.
+	#[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+	pub bounds: Option<Bounds>,
+	/// Default view of the tileset, as longitude, latitude and zoom level.
+	#[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+	pub center: Option<(f64, f64, u8)>,
+	/// URLs of GeoJSON files to display on the map.
+	#[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+	pub data: Vec<String>,
+	/// Description of the tileset.
+	#[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+	pub description: Option<String>,
+	/// Zoom level from which clients should generate missing tiles by overzooming.
+	#[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+	pub fillzoom: Option<u8>,
+	/// UTFGrid interactivity URL templates.
+	#[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+	pub grids: Vec<String>,
+	/// Legend to display with the map, as HTML.
+	#[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+	pub legend: Option<String>,
+	/// Highest zoom level of the tileset.
+	#[cfg_attr(feature = "serde", serde(default = "default_maxzoom"))]
+	pub maxzoom: u8,
+	/// Lowest zoom level of the tileset.
+	#[cfg_attr(feature = "serde", serde(default))]
+	pub minzoom: u8,
+	/// Name of the tileset.
+	#[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+	pub name: Option<String>,
+	/// Numbering scheme of the Y coordinate in the tile URLs.
+	#[cfg_attr(feature = "serde", serde(default))]
+	pub scheme: Scheme,
+	/// Mustache template formatting the UTFGrid data.
+	#[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+	pub template: Option<String>,
+	/// Version of the tileset, in the semver form.
+	#[cfg_attr(feature = "serde", serde(default = "default_version"))]
+	pub version: String,
+}
+
+impl Default for TileJson {
+	fn default() -> Self {
+		Self {
+			tilejson: TILEJSON_VERSION.to_string(),
+			tiles: Vec::new(),
+			vector_layers: Vec::new(),
+			attribution: None,
+			bounds: None,
+			center: None,
+			data: Vec::new(),
+			description: None,
+			fillzoom: None,
+			grids: Vec::new(),
+			legend: None,
+			maxzoom: default_maxzoom(),
+			minzoom: 0,
+			name: None,
+			scheme: Scheme::Xyz,
+			template: None,
+			version: default_version(),
+		}
+	}
+}
+
+fn default_maxzoom() -> u8 {
+	TILEJSON_MAX_ZOOM
+}
+
+fn default_version() -> String {
+	"1.0.0".to_string()
+}
+
+impl TileJson {
+	/// Create a document for the given tile URL templates, with every other field at its default.
+	///
+	/// # Arguments
+	///
+	/// * `tiles` - tile URL templates
+	pub fn new(tiles: impl IntoIterator<Item = impl Into<String>>) -> Self {
+		Self { tiles: tiles.into_iter().map(Into::into).collect(), ..Self::default() }
+	}
+
+	/// Set the bounds and center of the document to those of a tile range.
+	///
+	/// The zoom level of the center is the zoom level of the range.
+	///
+	/// # Arguments
+	///
+	/// * `range` - tiles of the tileset
+	pub fn set_range(&mut self, range: &TileRange) {
+		let (lon, lat) = range.center();
+		self.bounds = Some(range.bounds());
+		self.center = Some((lon, lat, range.zoom()));
+	}
+
+	/// Parse a TileJSON document.
+	#[cfg(feature = "serde")]
+	pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+		serde_json::from_str(json)
+	}
+
+	/// Generate the JSON encoding of the document.
+	#[cfg(feature = "serde")]
+	pub fn to_json(&self) -> String {
+		serde_json::to_string(self).expect("TileJSON documents only have string keys")
+	}
+}
+
+/// Layer of a vector tileset.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VectorLayer {
+	/// Name of the layer in the vector tiles.
+	pub id: String,
+	/// Attributes of the features of the layer, mapped to their description.
+	#[cfg_attr(feature = "serde", serde(default))]
+	pub fields: BTreeMap<String, String>,
+	/// Description of the layer.
+	#[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+	pub description: Option<String>,
+	/// Lowest zoom level at which the layer is present.
+	#[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+	pub minzoom: Option<u8>,
+	/// Highest zoom level at which the layer is present.
+	#[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+	pub maxzoom: Option<u8>,
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tile2lonlat;
+
+	#[test]
+	fn test_set_range() {
+		let mut tilejson = TileJson::new(["https://example.org/{z}/{x}/{y}.png"]);
+		assert_eq!((tilejson.minzoom, tilejson.maxzoom, tilejson.scheme), (0, 30, Scheme::Xyz));
+		tilejson.set_range(&TileRange::new(4376, 2932, 4377, 2935, 13).unwrap());
+		let (lon, lat) = tile2lonlat(4377, 2934, 13);
+		assert_eq!(tilejson.center, Some((lon, lat, 13)));
+		assert_eq!(tilejson.bounds.map(|b| (b.west, b.north)), Some(tile2lonlat(4376, 2932, 13)));
+	}
+
+	#[cfg(feature = "serde")]
+	#[test]
+	fn test_json() {
+		let mut tilejson = TileJson::new(["https://example.org/{z}/{x}/{y}.pbf"]);
+		tilejson.bounds = Some(Bounds::new(-180.0, -85.0, 180.0, 85.0));
+		tilejson.center = Some((0.0, 0.0, 2));
+		tilejson.maxzoom = 14;
+		tilejson.vector_layers.push(VectorLayer {
+			id: "roads".to_string(),
+			fields: BTreeMap::from([("class".to_string(), "String".to_string())]),
+			..VectorLayer::default()
+		});
+		let json = tilejson.to_json();
+		assert_eq!(
+			json,
+			concat!(
+				r#"{"tilejson":"3.0.0","tiles":["https://example.org/{z}/{x}/{y}.pbf"],"#,
+				r#""vector_layers":[{"id":"roads","fields":{"class":"String"}}],"#,
+				r#""bounds":[-180.0,-85.0,180.0,85.0],"center":[0.0,0.0,2],"#,
+				r#""maxzoom":14,"minzoom":0,"scheme":"xyz","version":"1.0.0"}"#,
+			)
+		);
+		assert_eq!(TileJson::from_json(&json).unwrap(), tilejson);
+
+		let tilejson = TileJson::from_json(r#"{"tilejson":"3.0.0","tiles":["/{z}/{x}/{y}.png"],"scheme":"tms"}"#).unwrap();
+		assert_eq!((tilejson.minzoom, tilejson.maxzoom, tilejson.scheme), (0, 30, Scheme::Tms));
+		assert!(TileJson::from_json(r#"{"tilejson":"3.0.0","tiles":"/{z}/{x}/{y}.png"}"#).is_err());
+		assert!(TileJson::from_json(r#"{"tiles":["/{z}/{x}/{y}.png"]}"#).is_err());
+		assert!(TileJson::from_json(r#"{"tilejson":"3.0.0"}"#).is_err());
+		assert!(TileJson::from_json("{}").is_err());
+	}
+}
@@ -0,0 +1,466 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{Scheme, Tile, TileError, MAX_ZOOM};
+
+/// Error returned when a tile URL template cannot be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateError {
+	/// The template contains a placeholder that is not supported.
+	UnknownPlaceholder(String),
+	/// The template contains a `{` without a matching `}`.
+	UnclosedPlaceholder,
+}
+
+impl fmt::Display for TemplateError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			TemplateError::UnknownPlaceholder(name) => write!(f, "unknown placeholder {{{name}}}"),
+			TemplateError::UnclosedPlaceholder => write!(f, "unclosed placeholder"),
+		}
+	}
+}
+
+impl Error for TemplateError {}
+
+/// Error returned when a tile cannot be parsed from the `z/x/y` form.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParseTileError {
+	/// The string is not a tile in the `z/x/y` form.
+	InvalidSyntax,
+	/// The coordinates are not valid for the zoom level.
+	InvalidTile(TileError),
+}
+
+impl fmt::Display for ParseTileError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			ParseTileError::InvalidSyntax => write!(f, "expected a tile in the z/x/y form"),
+			ParseTileError::InvalidTile(err) => write!(f, "invalid tile: {err}"),
+		}
+	}
+}
+
+impl Error for ParseTileError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			ParseTileError::InvalidTile(err) => Some(err),
+			ParseTileError::InvalidSyntax => None,
+		}
+	}
+}
+
+impl From<TileError> for ParseTileError {
+	fn from(err: TileError) -> Self {
+		ParseTileError::InvalidTile(err)
+	}
+}
+
+/// Parses a tile in the `z/x/y` form, checking that the coordinates are valid for the zoom level.
+impl FromStr for Tile {
+	type Err = ParseTileError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let mut parts = s.split('/');
+		let mut next = || parts.next().filter(|p| !p.is_empty() && p.bytes().all(|b| b.is_ascii_digit()));
+		let (z, x, y) = (next(), next(), next());
+		match (z, x, y, parts.next()) {
+			(Some(z), Some(x), Some(y), None) => {
+				// Numbers too large for their type saturate, and are then rejected by the range checks.
+				let number = |p: &str| p.parse::<u64>().unwrap_or(u64::MAX);
+				let (z, x, y) = (u8::try_from(number(z)).unwrap_or(u8::MAX), number(x), number(y));
+				match (u32::try_from(x), u32::try_from(y)) {
+					(Ok(x), Ok(y)) => Ok(Tile::new(x, y, z)?),
+					_ if z > MAX_ZOOM => Err(TileError::InvalidZoom(z).into()),
+					_ => Err(TileError::OutOfRange { x, y, z }.into()),
+				}
+			}
+			_ => Err(ParseTileError::InvalidSyntax),
+		}
+	}
+}
+
+/// Longest text matched by a bounding box placeholder, well above the four numbers of an expanded URL.
+const MAX_BBOX_LEN: usize = 128;
+
+/// Piece of a parsed template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Part {
+	Literal(String),
+	Z,
+	X,
+	Y,
+	FlippedY,
+	Quadkey,
+	Subdomain,
+	Retina,
+	BboxMeters,
+	BboxDegrees,
+}
+
+impl Part {
+	fn placeholder(&self) -> Option<&'static str> {
+		Some(match self {
+			Part::Literal(_) => return None,
+			Part::Z => "z",
+			Part::X => "x",
+			Part::Y => "y",
+			Part::FlippedY => "-y",
+			Part::Quadkey => "q",
+			Part::Subdomain => "s",
+			Part::Retina => "r",
+			Part::BboxMeters => "bbox-epsg-3857",
+			Part::BboxDegrees => "bbox-epsg-4326",
+		})
+	}
+}
+
+/// Tile URL template, such as `https://{s}.tile.example.org/{z}/{x}/{y}{r}.png`.
+///
+/// The supported placeholders are:
+///
+/// * `{z}`, `{x}`, `{y}` - zoom level and tile coordinates
+/// * `{-y}` - Y coordinate in the [`Scheme::Tms`] scheme
+/// * `{q}` - Bing Maps quadkey
+/// * `{s}` - subdomain, rotating between tiles
+/// * `{r}` - `@2x` for retina tiles, empty otherwise
+/// * `{bbox-epsg-3857}` - tile bounds in meters, as `west,south,east,north`
+/// * `{bbox-epsg-4326}` - tile bounds in degrees, as `west,south,east,north`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TileUrlTemplate {
+	pub(crate) parts: Vec<Part>,
+	subdomains: Vec<String>,
+	pub(crate) retina: bool,
+}
+
+impl TileUrlTemplate {
+	/// Parse a template.
+	///
+	/// The subdomains default to `a`, `b` and `c`, and retina tiles are disabled.
+	pub fn parse(template: &str) -> Result<Self, TemplateError> {
+		let mut parts = Vec::new();
+		let mut rest = template;
+		while let Some(start) = rest.find('{') {
+			if start > 0 {
+				parts.push(Part::Literal(rest[..start].to_string()));
+			}
+			let end = rest[start..].find('}').ok_or(TemplateError::UnclosedPlaceholder)? + start;
+			let name = &rest[start + 1..end];
+			parts.push(match name {
+				"z" => Part::Z,
+				"x" => Part::X,
+				"y" => Part::Y,
+				"-y" => Part::FlippedY,
+				"q" => Part::Quadkey,
+				"s" => Part::Subdomain,
+				"r" => Part::Retina,
+				"bbox-epsg-3857" => Part::BboxMeters,
+				"bbox-epsg-4326" => Part::BboxDegrees,
+				_ => return Err(TemplateError::UnknownPlaceholder(name.to_string())),
+			});
+			rest = &rest[end + 1..];
+		}
+		if !rest.is_empty() {
+			parts.push(Part::Literal(rest.to_string()));
+		}
+		Ok(Self { parts, subdomains: vec!["a".into(), "b".into(), "c".into()], retina: false })
+	}
+
+	/// Set the subdomains `{s}` rotates between. Without subdomains, `{s}` expands to nothing.
+	pub fn set_subdomains<S: Into<String>>(&mut self, subdomains: impl IntoIterator<Item = S>) {
+		self.subdomains = subdomains.into_iter().map(Into::into).collect();
+	}
+
+	/// Subdomains `{s}` rotates between.
+	pub fn subdomains(&self) -> &[String] {
+		&self.subdomains
+	}
+
+	/// Set whether `{r}` expands to `@2x`.
+	pub fn set_retina(&mut self, retina: bool) {
+		self.retina = retina;
+	}
+
+	/// Expand the template for a tile.
+	///
+	/// The subdomain is chosen from the tile coordinates, so that a tile always gets the same URL
+	/// while neighboring tiles are spread over all subdomains.
+	pub fn expand(&self, tile: Tile) -> String {
+		let mut url = String::new();
+		for part in &self.parts {
+			match part {
+				Part::Literal(s) => url.push_str(s),
+				Part::Z => url.push_str(&tile.z().to_string()),
+				Part::X => url.push_str(&tile.x().to_string()),
+				Part::Y => url.push_str(&tile.y().to_string()),
+				Part::FlippedY => url.push_str(&tile.y_in(Scheme::Tms).to_string()),
+				Part::Quadkey => url.push_str(&tile.to_quadkey()),
+				Part::Subdomain => {
+					if !self.subdomains.is_empty() {
+						let i = (tile.x() as u64 + tile.y() as u64) % self.subdomains.len() as u64;
+						url.push_str(&self.subdomains[i as usize]);
+					}
+				}
+				Part::Retina => {
+					if self.retina {
+						url.push_str("@2x");
+					}
+				}
+				Part::BboxMeters => url.push_str(&format_bbox(tile.bounds_meters().to_array())),
+				Part::BboxDegrees => url.push_str(&format_bbox(tile.bounds().to_array())),
+			}
+		}
+		url
+	}
+}
+
+impl TileUrlTemplate {
+	/// Recover the tile from a URL expanded from this template.
+	///
+	/// The tile is taken from `{z}`, `{x}`, `{y}`, `{-y}` and `{q}`, which must agree with each
+	/// other when several of them are present. `{s}` matches one of the subdomains, or nothing when
+	/// there are none, `{r}` matches `@2x` or nothing, and bounding boxes match four comma-separated
+	/// numbers without being used.
+	/// Returns `None` if the URL does not match the template or the tile is invalid.
+	pub fn match_url(&self, url: &str) -> Option<Tile> {
+		self.match_parts(&self.parts, url, Captures::default())
+	}
+
+	fn match_parts(&self, parts: &[Part], s: &str, captures: Captures) -> Option<Tile> {
+		let Some((part, parts)) = parts.split_first() else {
+			return if s.is_empty() { captures.tile() } else { None };
+		};
+
+		// Candidate lengths of the text matched by this part, tried from the longest. Coordinates are
+		// capped to the digits of the largest valid value, and bounding boxes to four numbers of
+		// reasonable length, to bound backtracking on long runs of digits.
+		let prefix_len = |max: usize, f: fn(u8) -> bool| s.bytes().take(max).take_while(|&b| f(b)).count();
+		let digits = |b: u8| b.is_ascii_digit();
+		let lengths: Vec<usize> = match part {
+			Part::Literal(literal) => s.starts_with(literal.as_str()).then_some(literal.len()).into_iter().collect(),
+			Part::Z => (1..=prefix_len(2, digits)).rev().collect(),
+			Part::X | Part::Y | Part::FlippedY => (1..=prefix_len(10, digits)).rev().collect(),
+			Part::Quadkey => (0..=prefix_len(MAX_ZOOM as usize, |b| (b'0'..=b'3').contains(&b))).rev().collect(),
+			Part::Subdomain if self.subdomains.is_empty() => vec![0],
+			Part::Subdomain => self.subdomains.iter().filter(|sub| s.starts_with(sub.as_str())).map(String::len).collect(),
+			Part::Retina => s.starts_with("@2x").then_some(3).into_iter().chain([0]).collect(),
+			Part::BboxMeters | Part::BboxDegrees => {
+				let is_bbox = |len: &usize| {
+					let numbers = s[..*len].split(',');
+					numbers.clone().count() == 4 && numbers.into_iter().all(|n| n.parse::<f64>().is_ok())
+				};
+				let len = prefix_len(MAX_BBOX_LEN, |b| b.is_ascii_digit() || b"-+.,eE".contains(&b));
+				(1..=len).rev().filter(is_bbox).collect()
+			}
+		};
+
+		lengths.into_iter().find_map(|len| {
+			let (matched, rest) = s.split_at(len);
+			let mut captures = captures.clone();
+			let number = || matched.parse::<u64>().ok();
+			match part {
+				Part::Z => captures.z = Some(number()?),
+				Part::X => captures.x = Some(number()?),
+				Part::Y => captures.y = Some(number()?),
+				Part::FlippedY => captures.flipped_y = Some(number()?),
+				Part::Quadkey => captures.quadkey = Some(Tile::from_quadkey(matched).ok()?),
+				_ => {}
+			}
+			self.match_parts(parts, rest, captures)
+		})
+	}
+}
+
+/// Values captured while matching a URL against a template.
+#[derive(Debug, Clone, Default)]
+struct Captures {
+	z: Option<u64>,
+	x: Option<u64>,
+	y: Option<u64>,
+	flipped_y: Option<u64>,
+	quadkey: Option<Tile>,
+}
+
+impl Captures {
+	/// Build the tile from the captured values, checking that they agree.
+	fn tile(&self) -> Option<Tile> {
+		let q = self.quadkey;
+		let z = u8::try_from(self.z.or(q.map(|t| t.z() as u64))?).ok()?;
+		let x = u32::try_from(self.x.or(q.map(|t| t.x() as u64))?).ok()?;
+		let tile = match (self.y, self.flipped_y) {
+			(Some(y), _) => Tile::new(x, u32::try_from(y).ok()?, z).ok()?,
+			(None, Some(y)) => Tile::with_scheme(x, u32::try_from(y).ok()?, z, Scheme::Tms).ok()?,
+			(None, None) => Tile::new(x, q?.y(), z).ok()?,
+		};
+		let consistent = self.flipped_y.iter().all(|&y| y == tile.y_in(Scheme::Tms) as u64) && q.iter().all(|&q| q == tile);
+		consistent.then_some(tile)
+	}
+}
+
+/// Matcher recovering tiles from URLs expanded from any of several templates.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TileUrlMatcher {
+	templates: Vec<TileUrlTemplate>,
+}
+
+impl TileUrlMatcher {
+	/// Create a matcher trying the templates in order.
+	pub fn new(templates: impl IntoIterator<Item = TileUrlTemplate>) -> Self {
+		Self { templates: templates.into_iter().collect() }
+	}
+
+	/// Add a template, tried after the existing ones.
+	pub fn push(&mut self, template: TileUrlTemplate) {
+		self.templates.push(template);
+	}
+
+	/// Recover the tile from a URL, using the first template matching it.
+	///
+	/// See [`TileUrlTemplate::match_url`] for how a URL is matched.
+	pub fn match_url(&self, url: &str) -> Option<Tile> {
+		self.templates.iter().find_map(|template| template.match_url(url))
+	}
+}
+
+fn format_bbox(bbox: [f64; 4]) -> String {
+	bbox.map(|v| v.to_string()).join(",")
+}
+
+impl FromStr for TileUrlTemplate {
+	type Err = TemplateError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Self::parse(s)
+	}
+}
+
+impl fmt::Display for TileUrlTemplate {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for part in &self.parts {
+			match (part, part.placeholder()) {
+				(Part::Literal(s), _) => f.write_str(s)?,
+				(_, Some(name)) => write!(f, "{{{name}}}")?,
+				(_, None) => {}
+			}
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_str() {
+		let tile = Tile::new(4376, 2932, 13).unwrap();
+		assert_eq!(tile.to_string(), "13/4376/2932");
+		assert_eq!("13/4376/2932".parse(), Ok(tile));
+		assert_eq!("0/0/0".parse::<Tile>().map(<(u32, u32, u8)>::from), Ok((0, 0, 0)));
+		assert_eq!("1/2/0".parse::<Tile>(), Err(TileError::OutOfRange { x: 2, y: 0, z: 1 }.into()));
+		assert_eq!("32/0/0".parse::<Tile>(), Err(TileError::InvalidZoom(32).into()));
+		assert_eq!("1000/0/0".parse::<Tile>(), Err(TileError::InvalidZoom(255).into()));
+		assert_eq!("3/5000000000/0".parse::<Tile>(), Err(TileError::OutOfRange { x: 5000000000, y: 0, z: 3 }.into()));
+		for invalid in ["", "13/4376", "13/4376/2932/1", "13/-1/0", "a/b/c", "13//2932", " 1/0/0"] {
+			assert_eq!(invalid.parse::<Tile>(), Err(ParseTileError::InvalidSyntax));
+		}
+	}
+
+	#[test]
+	fn test_expand() {
+		let mut template = TileUrlTemplate::parse("https://{s}.tile.example.org/{z}/{x}/{y}{r}.png").unwrap();
+		let tile = Tile::new(4376, 2932, 13).unwrap();
+		assert_eq!(template.expand(tile), "https://a.tile.example.org/13/4376/2932.png");
+		assert_eq!(template.expand(Tile::new(4377, 2932, 13).unwrap()), "https://b.tile.example.org/13/4377/2932.png");
+
+		template.set_retina(true);
+		template.set_subdomains(["t0", "t1"]);
+		assert_eq!(template.expand(tile), "https://t0.tile.example.org/13/4376/2932@2x.png");
+
+		let template: TileUrlTemplate = "/tms/{z}/{x}/{-y}.png?key={q}".parse().unwrap();
+		assert_eq!(template.expand(tile), "/tms/13/4376/5259.png?key=1202302231200");
+	}
+
+	#[test]
+	fn test_expand_bbox() {
+		let template = TileUrlTemplate::parse("/wms?BBOX={bbox-epsg-3857}&WIDTH=256").unwrap();
+		let tile = Tile::new(0, 1, 1).unwrap();
+		assert_eq!(template.expand(tile), "/wms?BBOX=-20037508.342789244,-20037508.342789244,0,0&WIDTH=256");
+		let template = TileUrlTemplate::parse("{bbox-epsg-4326}").unwrap();
+		assert_eq!(template.expand(Tile::new(1, 0, 1).unwrap()), "0,0,180,85.0511287798066");
+	}
+
+	#[test]
+	fn test_match() {
+		let template = TileUrlTemplate::parse("https://{s}.tile.example.org/{z}/{x}/{y}{r}.png").unwrap();
+		let t = Tile::new(4376, 2932, 13).unwrap();
+		assert_eq!(template.match_url("https://b.tile.example.org/13/4376/2932.png"), Some(t));
+		assert_eq!(template.match_url("https://a.tile.example.org/13/4376/2932@2x.png"), Some(t));
+		assert_eq!(template.match_url("https://d.tile.example.org/13/4376/2932.png"), None);
+		assert_eq!(template.match_url("https://a.tile.example.org/13/4376/9000.png"), None);
+		assert_eq!(template.match_url("https://a.tile.example.org/13/4376/2932.jpg"), None);
+
+		let mut template = TileUrlTemplate::parse("https://tile{s}.example.org/{z}/{x}/{y}.png").unwrap();
+		template.set_subdomains(Vec::<String>::new());
+		assert_eq!(template.expand(t), "https://tile.example.org/13/4376/2932.png");
+		assert_eq!(template.match_url(&template.expand(t)), Some(t));
+
+		let template = TileUrlTemplate::parse("/{z}{x}{y}").unwrap();
+		assert_eq!(template.match_url("/372"), Some(Tile::new(7, 2, 3).unwrap()));
+
+		let template = TileUrlTemplate::parse("/tms/{z}/{x}/{-y}.png").unwrap();
+		assert_eq!(template.match_url("/tms/13/4376/5259.png"), Some(t));
+
+		let template = TileUrlTemplate::parse("/tiles/{q}.jpeg").unwrap();
+		assert_eq!(template.match_url("/tiles/1202302231200.jpeg"), Some(t));
+		assert_eq!(template.match_url("/tiles/.jpeg"), Some(Tile::new(0, 0, 0).unwrap()));
+		assert_eq!(template.match_url("/tiles/12a.jpeg"), None);
+
+		let template = TileUrlTemplate::parse("/{z}/{x}/{y}?q={q}&bbox={bbox-epsg-3857}").unwrap();
+		assert_eq!(template.match_url("/1/0/1?q=2&bbox=-2.0e7,-2.0e7,0,0"), Some(Tile::new(0, 1, 1).unwrap()));
+		assert_eq!(template.match_url("/1/0/1?q=3&bbox=-2.0e7,-2.0e7,0,0"), None);
+	}
+
+	#[test]
+	fn test_match_bbox_long() {
+		let template = TileUrlTemplate::parse("/{z}/{x}/{y}/{bbox-epsg-3857}{bbox-epsg-4326}.png").unwrap();
+		let tile = Tile::new(4376, 2932, 13).unwrap();
+		assert_eq!(template.match_url(&template.expand(tile)), Some(tile));
+		let deep = Tile::new(1, (1 << 30) - 1, MAX_ZOOM).unwrap();
+		assert_eq!(template.match_url(&template.expand(deep)), Some(deep));
+		assert_eq!(template.match_url(&format!("/13/4376/2932/{}.jpg", "1,".repeat(1500))), None);
+		assert_eq!(template.match_url("/13/4376/2932/1,2,3,4,5,6,7,8,9.png"), None);
+		assert_eq!(template.match_url("/13/4376/2932/1,2,3,45,6,7,8.png"), Some(tile));
+	}
+
+	#[test]
+	fn test_match_long() {
+		let template = TileUrlTemplate::parse("/{z}{x}{y}{-y}{q}").unwrap();
+		let tile = Tile::new(4376, 2932, 13).unwrap();
+		assert_eq!(template.match_url(&template.expand(tile)), Some(tile));
+		// Runs of digits longer than any coordinate are rejected without trying every split.
+		assert_eq!(template.match_url(&format!("/{}", "1".repeat(10_000))), None);
+		assert_eq!(template.match_url("/130000000004376"), None);
+	}
+
+	#[test]
+	fn test_matcher() {
+		let templates = ["/xyz/{z}/{x}/{y}.png", "/tms/{z}/{x}/{-y}.png", "/{q}"].map(|t| t.parse().unwrap());
+		let matcher = TileUrlMatcher::new(templates);
+		let t = Tile::new(4376, 2932, 13).unwrap();
+		for template in &matcher.templates {
+			assert_eq!(matcher.match_url(&template.expand(t)), Some(t));
+		}
+		assert_eq!(matcher.match_url("/other/13/4376/2932.png"), None);
+	}
+
+	#[test]
+	fn test_parse() {
+		let s = "https://{s}.example.org/{z}/{x}/{-y}{r}.png?{q}&{bbox-epsg-3857}";
+		assert_eq!(TileUrlTemplate::parse(s).unwrap().to_string(), s);
+		assert_eq!(TileUrlTemplate::parse("{z}/{w}"), Err(TemplateError::UnknownPlaceholder("w".into())));
+		assert_eq!(TileUrlTemplate::parse("{z}/{x"), Err(TemplateError::UnclosedPlaceholder));
+		assert_eq!(TileUrlTemplate::parse("static.png").unwrap().expand(Tile::new(0, 0, 0).unwrap()), "static.png");
+	}
+}
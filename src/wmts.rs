@@ -0,0 +1,301 @@
+use std::fmt::Write;
+use std::ops::RangeInclusive;
+
+use crate::url::Part;
+use crate::{
+	tile_bounds, Bounds, CornerOfOrigin, TileError, TileMatrixError, TileMatrixSet, TileRange, TileUrlTemplate,
+};
+
+/// Layer of a WMTS service, see [`WmtsCapabilities`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct WmtsLayer {
+	/// Identifier of the layer, used by clients in their requests.
+	pub identifier: String,
+	/// Human-readable title.
+	pub title: String,
+	/// Description of the layer.
+	pub description: Option<String>,
+	/// MIME type of the tiles, such as `image/png`.
+	pub format: String,
+	/// URL template of the tiles, with the `{TileMatrix}`, `{TileRow}` and `{TileCol}` placeholders.
+	pub resource_url: String,
+	/// Extent of the layer in degrees.
+	pub bounds: Bounds,
+	/// Zoom levels at which the layer has tiles.
+	pub zooms: RangeInclusive<u8>,
+}
+
+impl WmtsLayer {
+	/// Create a layer covering the whole world, titled after its identifier.
+	///
+	/// # Arguments
+	///
+	/// * `identifier`   - identifier of the layer
+	/// * `format`       - MIME type of the tiles
+	/// * `resource_url` - URL template of the tiles, see [`TileUrlTemplate::to_wmts`]
+	/// * `zooms`        - zoom levels at which the layer has tiles
+	pub fn new(
+		identifier: impl Into<String>,
+		format: impl Into<String>,
+		resource_url: impl Into<String>,
+		zooms: RangeInclusive<u8>,
+	) -> Self {
+		let identifier = identifier.into();
+		Self {
+			title: identifier.clone(),
+			identifier,
+			description: None,
+			format: format.into(),
+			resource_url: resource_url.into(),
+			bounds: tile_bounds(0, 0, 0),
+			zooms,
+		}
+	}
+
+	/// Set the extent of the layer to the bounds of a tile range.
+	///
+	/// # Arguments
+	///
+	/// * `range` - tiles of the layer
+	pub fn set_range(&mut self, range: &TileRange) {
+		self.bounds = range.bounds();
+	}
+}
+
+/// Generator of WMTS 1.0.0 GetCapabilities documents, for layers sharing a [`TileMatrixSet`].
+///
+/// Each layer is described with the limits of the tiles covering its bounds at each of its
+/// zoom levels. Tile matrices with a bottom-left corner of origin are described from their
+/// top-left corner, as required by WMTS, so their rows are counted from the top.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WmtsCapabilities {
+	/// Title of the service.
+	pub title: String,
+	/// Tile matrix set of the layers.
+	pub tile_matrix_set: TileMatrixSet,
+	/// Layers of the service.
+	pub layers: Vec<WmtsLayer>,
+}
+
+impl WmtsCapabilities {
+	/// Create a service without any layer.
+	///
+	/// # Arguments
+	///
+	/// * `title`           - title of the service
+	/// * `tile_matrix_set` - tile matrix set of the layers
+	pub fn new(title: impl Into<String>, tile_matrix_set: TileMatrixSet) -> Self {
+		Self { title: title.into(), tile_matrix_set, layers: Vec::new() }
+	}
+
+	/// Generate the GetCapabilities XML document.
+	///
+	/// Fails if a layer has zoom levels missing from the tile matrix set, invalid bounds,
+	/// or if the coordinate reference system of the tile matrix set is not supported.
+	pub fn to_xml(&self) -> Result<String, TileMatrixError> {
+		let tms = &self.tile_matrix_set;
+		let mut xml = String::new();
+		xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+		xml.push_str(concat!(
+			"<Capabilities xmlns=\"http://www.opengis.net/wmts/1.0\" xmlns:ows=\"http://www.opengis.net/ows/1.1\"",
+			" xmlns:xlink=\"http://www.w3.org/1999/xlink\" xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\"",
+			" xsi:schemaLocation=\"http://www.opengis.net/wmts/1.0",
+			" http://schemas.opengis.net/wmts/1.0/wmtsGetCapabilities_response.xsd\" version=\"1.0.0\">\n",
+		));
+		xml.push_str("\t<ows:ServiceIdentification>\n");
+		element(&mut xml, 2, "ows:Title", &self.title);
+		element(&mut xml, 2, "ows:ServiceType", "OGC WMTS");
+		element(&mut xml, 2, "ows:ServiceTypeVersion", "1.0.0");
+		xml.push_str("\t</ows:ServiceIdentification>\n");
+		xml.push_str("\t<Contents>\n");
+
+		for layer in &self.layers {
+			xml.push_str("\t\t<Layer>\n");
+			element(&mut xml, 3, "ows:Title", &layer.title);
+			if let Some(description) = &layer.description {
+				element(&mut xml, 3, "ows:Abstract", description);
+			}
+			let Bounds { west, south, east, north } = layer.bounds;
+			xml.push_str("\t\t\t<ows:WGS84BoundingBox>\n");
+			element(&mut xml, 4, "ows:LowerCorner", &format!("{west} {south}"));
+			element(&mut xml, 4, "ows:UpperCorner", &format!("{east} {north}"));
+			xml.push_str("\t\t\t</ows:WGS84BoundingBox>\n");
+			element(&mut xml, 3, "ows:Identifier", &layer.identifier);
+			xml.push_str("\t\t\t<Style isDefault=\"true\">\n");
+			element(&mut xml, 4, "ows:Identifier", "default");
+			xml.push_str("\t\t\t</Style>\n");
+			element(&mut xml, 3, "Format", &layer.format);
+
+			xml.push_str("\t\t\t<TileMatrixSetLink>\n");
+			element(&mut xml, 4, "TileMatrixSet", &tms.id);
+			xml.push_str("\t\t\t\t<TileMatrixSetLimits>\n");
+			for zoom in layer.zooms.clone() {
+				let mut limits = tms.tile_range(&layer.bounds, zoom)?;
+				let matrix = tms.tile_matrix(zoom).ok_or(TileError::InvalidZoom(zoom))?;
+				if matrix.corner_of_origin == CornerOfOrigin::BottomLeft {
+					let last = matrix.matrix_height - 1;
+					(limits.min_tile_row, limits.max_tile_row) = (last - limits.max_tile_row, last - limits.min_tile_row);
+				}
+				xml.push_str("\t\t\t\t\t<TileMatrixLimits>\n");
+				element(&mut xml, 6, "TileMatrix", &limits.tile_matrix);
+				element(&mut xml, 6, "MinTileRow", &limits.min_tile_row.to_string());
+				element(&mut xml, 6, "MaxTileRow", &limits.max_tile_row.to_string());
+				element(&mut xml, 6, "MinTileCol", &limits.min_tile_col.to_string());
+				element(&mut xml, 6, "MaxTileCol", &limits.max_tile_col.to_string());
+				xml.push_str("\t\t\t\t\t</TileMatrixLimits>\n");
+			}
+			xml.push_str("\t\t\t\t</TileMatrixSetLimits>\n");
+			xml.push_str("\t\t\t</TileMatrixSetLink>\n");
+			let (format, template) = (escape(&layer.format), escape(&layer.resource_url));
+			let _ = writeln!(xml, "\t\t\t<ResourceURL format=\"{format}\" resourceType=\"tile\" template=\"{template}\"/>");
+			xml.push_str("\t\t</Layer>\n");
+		}
+
+		xml.push_str("\t\t<TileMatrixSet>\n");
+		if let Some(title) = &tms.title {
+			element(&mut xml, 3, "ows:Title", title);
+		}
+		element(&mut xml, 3, "ows:Identifier", &tms.id);
+		element(&mut xml, 3, "ows:SupportedCRS", &crs_urn(&tms.crs));
+		for matrix in &tms.tile_matrices {
+			let (left, top) = match matrix.corner_of_origin {
+				CornerOfOrigin::TopLeft => matrix.tile_to_crs(0f64, 0f64),
+				CornerOfOrigin::BottomLeft => matrix.tile_to_crs(0f64, matrix.matrix_height as f64),
+			};
+			xml.push_str("\t\t\t<TileMatrix>\n");
+			element(&mut xml, 4, "ows:Identifier", &matrix.id);
+			element(&mut xml, 4, "ScaleDenominator", &matrix.scale_denominator.to_string());
+			element(&mut xml, 4, "TopLeftCorner", &format!("{left} {top}"));
+			element(&mut xml, 4, "TileWidth", &matrix.tile_width.to_string());
+			element(&mut xml, 4, "TileHeight", &matrix.tile_height.to_string());
+			element(&mut xml, 4, "MatrixWidth", &matrix.matrix_width.to_string());
+			element(&mut xml, 4, "MatrixHeight", &matrix.matrix_height.to_string());
+			xml.push_str("\t\t\t</TileMatrix>\n");
+		}
+		xml.push_str("\t\t</TileMatrixSet>\n");
+		xml.push_str("\t</Contents>\n");
+		xml.push_str("</Capabilities>\n");
+		Ok(xml)
+	}
+}
+
+impl TileUrlTemplate {
+	/// Convert the template to a WMTS resource URL template.
+	///
+	/// `{z}`, `{x}` and `{y}` become `{TileMatrix}`, `{TileCol}` and `{TileRow}`, `{s}` is replaced
+	/// by the first subdomain and `{r}` by `@2x` if retina tiles are enabled. Returns `None` if the
+	/// template uses `{-y}`, `{q}` or a bounding box, which cannot be expressed in WMTS.
+	pub fn to_wmts(&self) -> Option<String> {
+		let mut url = String::new();
+		for part in &self.parts {
+			match part {
+				Part::Literal(s) => url.push_str(s),
+				Part::Z => url.push_str("{TileMatrix}"),
+				Part::X => url.push_str("{TileCol}"),
+				Part::Y => url.push_str("{TileRow}"),
+				Part::Subdomain => url.push_str(self.subdomains().first().map_or("", String::as_str)),
+				Part::Retina => url.push_str(if self.retina { "@2x" } else { "" }),
+				Part::FlippedY | Part::Quadkey | Part::BboxMeters | Part::BboxDegrees => return None,
+			}
+		}
+		Some(url)
+	}
+}
+
+/// Append an element with escaped text content on its own line.
+fn element(xml: &mut String, indent: usize, name: &str, text: &str) {
+	let _ = writeln!(xml, "{}<{name}>{}</{name}>", "\t".repeat(indent), escape(text));
+}
+
+/// Escape the characters with a special meaning in XML text and attributes.
+fn escape(s: &str) -> String {
+	let mut escaped = String::with_capacity(s.len());
+	for c in s.chars() {
+		match c {
+			'&' => escaped.push_str("&amp;"),
+			'<' => escaped.push_str("&lt;"),
+			'>' => escaped.push_str("&gt;"),
+			'"' => escaped.push_str("&quot;"),
+			'\'' => escaped.push_str("&apos;"),
+			c => escaped.push(c),
+		}
+	}
+	escaped
+}
+
+/// Convert an OGC CRS URI such as `http://www.opengis.net/def/crs/EPSG/0/3857`
+/// to the URN form used by WMTS, such as `urn:ogc:def:crs:EPSG::3857`.
+fn crs_urn(crs: &str) -> String {
+	let path = crs.trim_start_matches("http://").trim_start_matches("https://");
+	match path.strip_prefix("www.opengis.net/def/crs/").map(|p| p.split('/').collect::<Vec<_>>()).as_deref() {
+		Some([authority, version, code]) => {
+			let version = if *version == "0" { "" } else { version };
+			format!("urn:ogc:def:crs:{authority}:{version}:{code}")
+		}
+		_ => crs.to_string(),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_capabilities() {
+		let template = TileUrlTemplate::parse("https://{s}.example.org/{z}/{x}/{y}.png?a=1&b=2").unwrap();
+		let mut layer = WmtsLayer::new("osm", "image/png", template.to_wmts().unwrap(), 12..=13);
+		layer.set_range(&TileRange::new(2188, 1466, 2189, 1466, 12).unwrap());
+		let mut capabilities = WmtsCapabilities::new("Tiles", TileMatrixSet::web_mercator_quad());
+		capabilities.layers.push(layer);
+		let xml = capabilities.to_xml().unwrap();
+
+		assert!(xml.contains(concat!(
+			"\t\t<TileMatrixSet>\n\t\t\t<ows:Title>Google Maps Compatible for the World</ows:Title>\n",
+			"\t\t\t<ows:Identifier>WebMercatorQuad</ows:Identifier>\n",
+			"\t\t\t<ows:SupportedCRS>urn:ogc:def:crs:EPSG::3857</ows:SupportedCRS>\n\t\t\t<TileMatrix>\n",
+		)));
+		let template = "https://a.example.org/{TileMatrix}/{TileCol}/{TileRow}.png?a=1&amp;b=2";
+		assert!(xml.contains(&format!("<ResourceURL format=\"image/png\" resourceType=\"tile\" template=\"{template}\"/>")));
+		assert!(xml.contains(concat!(
+			"<TileMatrix>13</TileMatrix>\n\t\t\t\t\t\t<MinTileRow>2932</MinTileRow>\n",
+			"\t\t\t\t\t\t<MaxTileRow>2933</MaxTileRow>\n\t\t\t\t\t\t<MinTileCol>4376</MinTileCol>\n",
+			"\t\t\t\t\t\t<MaxTileCol>4379</MaxTileCol>",
+		)));
+		let scale = capabilities.tile_matrix_set.tile_matrices[0].scale_denominator;
+		assert!((scale - 559082264.0287178).abs() < 1e-6);
+		assert!(xml.contains(&format!(
+			"<ows:Identifier>0</ows:Identifier>\n\t\t\t\t<ScaleDenominator>{scale}</ScaleDenominator>\n\t\t\t\t{}",
+			"<TopLeftCorner>-20037508.342789244 20037508.342789244</TopLeftCorner>",
+		)));
+		assert_eq!(xml.matches("<TileMatrixLimits>").count(), 2);
+		assert_eq!(xml.matches("<TileMatrix>\n").count(), 25);
+
+		capabilities.layers[0].zooms = 24..=25;
+		assert_eq!(capabilities.to_xml(), Err(TileMatrixError::Tile(TileError::InvalidZoom(25))));
+	}
+
+	#[test]
+	fn test_bottom_left() {
+		let mut tms = TileMatrixSet::world_crs84_quad();
+		tms.tile_matrices.truncate(2);
+		tms.tile_matrices[1].corner_of_origin = CornerOfOrigin::BottomLeft;
+		tms.tile_matrices[1].point_of_origin = [-180.0, -90.0];
+		let mut capabilities = WmtsCapabilities::new("Tiles", tms);
+		let mut layer = WmtsLayer::new("north", "image/jpeg", "/{TileMatrix}/{TileCol}/{TileRow}.jpg", 1..=1);
+		layer.bounds = Bounds::new(-180.0, 10.0, -100.0, 80.0);
+		capabilities.layers.push(layer);
+		let xml = capabilities.to_xml().unwrap();
+
+		assert!(xml.contains("<ows:SupportedCRS>urn:ogc:def:crs:OGC:1.3:CRS84</ows:SupportedCRS>"));
+		assert!(xml.contains("<MinTileRow>0</MinTileRow>\n\t\t\t\t\t\t<MaxTileRow>0</MaxTileRow>"));
+		assert_eq!(xml.matches("<TopLeftCorner>-180 90</TopLeftCorner>").count(), 2);
+	}
+
+	#[test]
+	fn test_to_wmts() {
+		let template = TileUrlTemplate::parse("/{z}/{x}/{y}{r}.png").unwrap();
+		assert_eq!(template.to_wmts().as_deref(), Some("/{TileMatrix}/{TileCol}/{TileRow}.png"));
+		assert_eq!(TileUrlTemplate::parse("/{z}/{x}/{-y}.png").unwrap().to_wmts(), None);
+		assert_eq!(TileUrlTemplate::parse("/{q}.png").unwrap().to_wmts(), None);
+	}
+}